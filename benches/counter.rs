@@ -0,0 +1,205 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::{
+	hint::black_box,
+	sync::{mpsc, Barrier},
+	thread,
+	time::{Duration, Instant},
+};
+use thread_counter::{ShardedThreadCounter, ThreadCounter};
+
+/// Increments and decrements from a single thread, with no contention.
+fn uncontended(c: &mut Criterion) {
+	let mut group = c.benchmark_group("uncontended");
+	let counter = ThreadCounter::default();
+	group.bench_function("increment_decrement", |b| {
+		b.iter(|| {
+			black_box(&counter).increment();
+			black_box(&counter).decrement();
+		})
+	});
+	group.bench_function("ticket", |b| {
+		b.iter(|| drop(black_box(&counter).ticket().unwrap()))
+	});
+	group.finish();
+}
+
+/// Increments and decrements from several threads at once.
+fn contended(c: &mut Criterion) {
+	let mut group = c.benchmark_group("contended");
+	for threads in [2, 4, 8] {
+		group.bench_with_input(
+			BenchmarkId::new("increment_decrement", threads),
+			&threads,
+			|b, &threads| {
+				b.iter_custom(|iters| {
+					let counter = ThreadCounter::default();
+					let barrier = Barrier::new(threads);
+					thread::scope(|scope| {
+						let handles = (0..threads)
+							.map(|_| {
+								scope.spawn(|| {
+									barrier.wait();
+									let start = Instant::now();
+									for _ in 0..iters {
+										counter.increment();
+										counter.decrement();
+									}
+									start.elapsed()
+								})
+							})
+							.collect::<Vec<_>>();
+						handles
+							.into_iter()
+							.map(|handle| handle.join().unwrap())
+							.max()
+							.unwrap_or_default()
+					})
+				})
+			},
+		);
+	}
+	group.finish();
+}
+
+/// Takes and drops tickets from several threads at once, comparing a regular
+/// counter against a sharded one.
+fn contended_tickets(c: &mut Criterion) {
+	let mut group = c.benchmark_group("contended_tickets");
+	for threads in [2, 4, 8] {
+		group.bench_with_input(
+			BenchmarkId::new("ticket", threads),
+			&threads,
+			|b, &threads| {
+				let counter = ThreadCounter::default();
+				b.iter_custom(|iters| {
+					time_threads(threads, || {
+						for _ in 0..iters {
+							drop(black_box(&counter).ticket().unwrap());
+						}
+					})
+				})
+			},
+		);
+		group.bench_with_input(
+			BenchmarkId::new("sharded_ticket", threads),
+			&threads,
+			|b, &threads| {
+				let counter = ShardedThreadCounter::default();
+				b.iter_custom(|iters| {
+					time_threads(threads, || {
+						for _ in 0..iters {
+							drop(black_box(&counter).ticket());
+						}
+					})
+				})
+			},
+		);
+	}
+	group.finish();
+}
+
+/// Runs `work` on `threads` threads at once, returning how long the slowest
+/// one took.
+fn time_threads(threads: usize, work: impl Fn() + Sync) -> Duration {
+	let barrier = Barrier::new(threads);
+	thread::scope(|scope| {
+		let handles = (0..threads)
+			.map(|_| {
+				scope.spawn(|| {
+					barrier.wait();
+					let start = Instant::now();
+					work();
+					start.elapsed()
+				})
+			})
+			.collect::<Vec<_>>();
+		handles
+			.into_iter()
+			.map(|handle| handle.join().unwrap())
+			.max()
+			.unwrap_or_default()
+	})
+}
+
+/// Measures how long it takes for a thread blocked in `wait` to wake up after
+/// the count reaches zero, comparing a regular counter against one using
+/// atomic waits.
+fn wait_latency(c: &mut Criterion) {
+	let mut group = c.benchmark_group("wait_latency");
+	group.bench_function("thread_counter", |b| {
+		let counter = ThreadCounter::default();
+		b.iter_custom(|iters| {
+			time_wakeups(
+				iters,
+				|| counter.increment(),
+				|| counter.decrement(),
+				|| {
+					counter.wait(None);
+				},
+				|| counter.waiters(),
+			)
+		})
+	});
+	#[cfg(feature = "atomic-wait")]
+	group.bench_function("atomic_wait", |b| {
+		let counter = thread_counter::AtomicWaitCounter::new();
+		b.iter_custom(|iters| {
+			time_wakeups(
+				iters,
+				|| counter.increment(),
+				|| counter.decrement(),
+				|| {
+					counter.wait_indefinitely();
+				},
+				|| counter.waiters(),
+			)
+		})
+	});
+	group.finish();
+}
+
+/// Has a thread `wait` `iters` times, returning the total time from each
+/// `decrement` to that thread waking up.
+fn time_wakeups(
+	iters: u64,
+	increment: impl Fn(),
+	decrement: impl Fn(),
+	wait: impl Fn() + Sync,
+	waiters: impl Fn() -> usize,
+) -> Duration {
+	let (go_tx, go_rx) = mpsc::channel::<()>();
+	let (done_tx, done_rx) = mpsc::channel::<()>();
+	let wait = &wait;
+	thread::scope(|scope| {
+		scope.spawn(move || {
+			while go_rx.recv().is_ok() {
+				wait();
+				done_tx.send(()).unwrap();
+			}
+		});
+		let mut total = Duration::ZERO;
+		for _ in 0..iters {
+			increment();
+			go_tx.send(()).unwrap();
+			while waiters() == 0 {
+				thread::yield_now();
+			}
+			let start = Instant::now();
+			decrement();
+			done_rx.recv().unwrap();
+			total += start.elapsed();
+		}
+		drop(go_tx);
+		total
+	})
+}
+
+criterion_group!(
+	benches,
+	uncontended,
+	contended,
+	contended_tickets,
+	wait_latency
+);
+criterion_main!(benches);
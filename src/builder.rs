@@ -0,0 +1,321 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+#[cfg(feature = "std")]
+use crate::DurationCallback;
+use crate::{
+	sync::{atomic::AtomicUsize, Mutex},
+	Callback, CountCallback, HighWater, RawThreadCounter, ThreadCounter,
+};
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use core::fmt;
+#[cfg(feature = "std")]
+use core::time::Duration;
+
+/// A builder for configuring a [`ThreadCounter`], created by
+/// [`ThreadCounter::builder()`].
+///
+/// This gives a single place to combine options, rather than needing a
+/// separate constructor for every combination of them.
+///
+/// ```rust
+/// use thread_counter::ThreadCounter;
+///
+/// let counter = ThreadCounter::builder()
+/// 	.capacity(4)
+/// 	.initial_count(1)
+/// 	.on_drain(|| println!("all done!"))
+/// 	.build();
+/// assert_eq!(counter.capacity(), Some(4));
+/// assert_eq!(counter.count(), 1);
+/// ```
+#[derive(Default)]
+#[must_use = "a builder does nothing until `build()` is called"]
+pub struct ThreadCounterBuilder {
+	capacity: Option<usize>,
+	fair: bool,
+	initial_count: usize,
+	drain_callbacks: Vec<Callback>,
+	activate_callbacks: Vec<Callback>,
+	leak_callback: Option<CountCallback>,
+	high_water: Option<HighWater>,
+	expect_zero_on_drop: bool,
+	#[cfg(feature = "std")]
+	track_idle: bool,
+	#[cfg(feature = "std")]
+	slow_callback: Option<DurationCallback>,
+	#[cfg(feature = "metrics")]
+	metric: Option<metrics::SharedString>,
+}
+
+impl ThreadCounterBuilder {
+	/// Limits the counter to at most `max` tickets at once.
+	///
+	/// See [`ThreadCounter::with_capacity()`].
+	///
+	/// # Panics
+	/// Panics if `max` is zero, as no ticket could ever be acquired.
+	pub fn capacity(mut self, max: usize) -> Self {
+		assert!(max > 0, "thread counter capacity must be nonzero");
+		self.capacity = Some(max);
+		self
+	}
+
+	/// Makes threads blocked in [`ThreadCounter::ticket()`] waiting for
+	/// capacity take their tickets in the order they started waiting.
+	///
+	/// By default, whichever thread gets there first after a ticket is
+	/// dropped wins, so a thread can be starved by a steady stream of
+	/// newcomers. In fair mode, threads queue up instead, and
+	/// [`ThreadCounter::try_ticket()`] fails while anyone is queued.
+	///
+	/// This has no effect without a [capacity](Self::capacity()).
+	///
+	/// # Performance
+	/// Fairness costs throughput: a thread can't take a free slot while it
+	/// isn't its turn, even if the thread whose turn it is hasn't woken up yet,
+	/// and every hand-over wakes up all waiting threads to find the next one.
+	pub fn fair(mut self, fair: bool) -> Self {
+		self.fair = fair;
+		self
+	}
+
+	/// Starts the counter at `count` rather than zero.
+	///
+	/// See [`ThreadCounter::with_count()`].
+	pub fn initial_count(mut self, count: usize) -> Self {
+		self.initial_count = count;
+		self
+	}
+
+	/// Registers a callback to be run whenever the count drops to zero.
+	///
+	/// See [`RawThreadCounter::on_drain()`]. This can be called multiple
+	/// times to register multiple callbacks.
+	pub fn on_drain(mut self, f: impl Fn() + Send + Sync + 'static) -> Self {
+		self.drain_callbacks.push(Arc::new(f));
+		self
+	}
+
+	/// Registers a callback to be run whenever the count rises from zero.
+	///
+	/// See [`RawThreadCounter::on_activate()`]. This can be called multiple
+	/// times to register multiple callbacks.
+	///
+	/// ```rust
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::builder()
+	/// 	.on_activate(|| println!("starting up"))
+	/// 	.on_deactivate(|| println!("shutting down"))
+	/// 	.build();
+	/// drop(counter.ticket().unwrap());
+	/// ```
+	pub fn on_activate(mut self, f: impl Fn() + Send + Sync + 'static) -> Self {
+		self.activate_callbacks.push(Arc::new(f));
+		self
+	}
+
+	/// Registers a callback to be run whenever the count drops to zero.
+	///
+	/// This is the same as [`Self::on_drain()`], under a name that pairs with
+	/// [`Self::on_activate()`].
+	pub fn on_deactivate(self, f: impl Fn() + Send + Sync + 'static) -> Self {
+		self.on_drain(f)
+	}
+
+	/// Registers a callback to be run if the counter is dropped while the
+	/// count is still nonzero, which is given the leftover count.
+	///
+	/// Tickets keep their counter alive, so a nonzero count at that point
+	/// means that manual increments were never balanced out, e.g. because a
+	/// thread panicked between [`RawThreadCounter::increment()`] and
+	/// [`RawThreadCounter::decrement()`], or a ticket was forgotten. This is
+	/// mostly useful for catching such mistakes in tests. Only one callback
+	/// can be registered; registering another replaces it.
+	///
+	/// ```rust
+	/// use std::sync::{
+	/// 	atomic::{AtomicUsize, Ordering},
+	/// 	Arc,
+	/// };
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let leaked = Arc::new(AtomicUsize::new(0));
+	/// let counter = ThreadCounter::builder()
+	/// 	.on_leak({
+	/// 		let leaked = leaked.clone();
+	/// 		move |count| leaked.store(count, Ordering::Relaxed)
+	/// 	})
+	/// 	.build();
+	///
+	/// counter.increment_by(2);
+	/// drop(counter);
+	/// assert_eq!(leaked.load(Ordering::Relaxed), 2);
+	/// ```
+	pub fn on_leak(mut self, f: impl Fn(usize) + Send + Sync + 'static) -> Self {
+		self.leak_callback = Some(Box::new(f));
+		self
+	}
+
+	/// Makes dropping the counter while the count is still nonzero fail a
+	/// debug assertion.
+	///
+	/// This is a stricter version of [`Self::on_leak()`], for catching leaked
+	/// tickets and unbalanced increments in tests and CI, without needing to
+	/// check for them by hand. It's only checked in builds with debug
+	/// assertions enabled, and not while the thread is already panicking,
+	/// so as not to abort and hide the original panic. A leak callback, if
+	/// any, is still run first.
+	///
+	/// ```rust,should_panic
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::builder().expect_zero_on_drop(true).build();
+	/// counter.increment();
+	/// // Panics in debug builds, as the count is still 1.
+	/// drop(counter);
+	/// ```
+	pub fn expect_zero_on_drop(mut self, expect: bool) -> Self {
+		self.expect_zero_on_drop = expect;
+		self
+	}
+
+	/// Registers a callback to be run when the count rises to `threshold`,
+	/// which is given the count at that point.
+	///
+	/// This is meant for early warnings of backpressure, before any capacity
+	/// limit is hit. To avoid flooding you with warnings while the count
+	/// hovers around the threshold, the callback only fires once, until the
+	/// count has dropped back down to half the threshold or below. Only one
+	/// callback can be registered; registering another replaces it.
+	///
+	/// # Note
+	/// The callback is run on the thread that performed the increment,
+	/// before it returns, so it should be quick.
+	///
+	/// ```rust
+	/// use std::sync::{
+	/// 	atomic::{AtomicUsize, Ordering},
+	/// 	Arc,
+	/// };
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let warnings = Arc::new(AtomicUsize::new(0));
+	/// let counter = ThreadCounter::builder()
+	/// 	.on_high_water(4, {
+	/// 		let warnings = warnings.clone();
+	/// 		move |_count| {
+	/// 			warnings.fetch_add(1, Ordering::Relaxed);
+	/// 		}
+	/// 	})
+	/// 	.build();
+	///
+	/// counter.increment_by(4);
+	/// assert_eq!(warnings.load(Ordering::Relaxed), 1);
+	/// // Hovering around the threshold doesn't fire it again...
+	/// counter.decrement();
+	/// counter.increment();
+	/// assert_eq!(warnings.load(Ordering::Relaxed), 1);
+	/// // ...but dropping back down to half of it re-arms it.
+	/// counter.decrement_by(2);
+	/// counter.increment_by(2);
+	/// assert_eq!(warnings.load(Ordering::Relaxed), 2);
+	/// ```
+	pub fn on_high_water(
+		mut self,
+		threshold: usize,
+		f: impl Fn(usize) + Send + Sync + 'static,
+	) -> Self {
+		self.high_water = Some(HighWater::new(threshold, Box::new(f)));
+		self
+	}
+
+	/// Records when the count last changed, for
+	/// [`RawThreadCounter::idle_for()`].
+	///
+	/// This is off by default, as reading the clock on every increment and
+	/// decrement costs more than the atomic operation it would sit next to.
+	#[cfg(feature = "std")]
+	pub fn track_idle(mut self, track: bool) -> Self {
+		self.track_idle = track;
+		self
+	}
+
+	/// Registers a callback to be run when a
+	/// [`TimedTicket`](crate::TimedTicket) is dropped after being held for
+	/// longer than it was meant to, which is given how long it was held for.
+	///
+	/// See [`ThreadCounter::ticket_timed()`]. Only one callback can be
+	/// registered; registering another replaces it.
+	///
+	/// # Note
+	/// The callback is run on the thread that dropped the ticket, before the
+	/// count is decremented, so it should be quick.
+	#[cfg(feature = "std")]
+	pub fn on_slow_ticket(mut self, f: impl Fn(Duration) + Send + Sync + 'static) -> Self {
+		self.slow_callback = Some(Box::new(f));
+		self
+	}
+
+	/// Mirrors the count into a `metrics` gauge called `name`, updating it
+	/// whenever the count changes.
+	///
+	/// # Note
+	/// The gauge is registered when the counter is built, so the global
+	/// recorder must be installed before calling [`Self::build()`]. Otherwise,
+	/// the updates go nowhere.
+	#[cfg(feature = "metrics")]
+	pub fn metric(mut self, name: impl Into<metrics::SharedString>) -> Self {
+		self.metric = Some(name.into());
+		self
+	}
+
+	/// Creates the configured [`ThreadCounter`].
+	pub fn build(self) -> ThreadCounter {
+		#[cfg(feature = "metrics")]
+		let gauge = self.metric.map(|name| {
+			let gauge = metrics::gauge!(name);
+			gauge.set(self.initial_count as f64);
+			gauge
+		});
+		let mut counter = RawThreadCounter::with_count(self.initial_count);
+		counter.capacity = self.capacity;
+		counter.fair = self.fair;
+		counter.drain_callback_count = AtomicUsize::new(self.drain_callbacks.len());
+		counter.drain_callbacks = Mutex::new(self.drain_callbacks);
+		counter.activate_callback_count = AtomicUsize::new(self.activate_callbacks.len());
+		counter.activate_callbacks = Mutex::new(self.activate_callbacks);
+		counter.leak_callback = self.leak_callback;
+		counter.high_water = self.high_water;
+		counter.expect_zero_on_drop = self.expect_zero_on_drop;
+		#[cfg(feature = "std")]
+		{
+			counter.track_idle = self.track_idle;
+			counter.slow_callback = self.slow_callback;
+		}
+		#[cfg(feature = "metrics")]
+		{
+			counter.gauge = gauge;
+		}
+		ThreadCounter {
+			inner: Arc::new(counter),
+		}
+	}
+}
+
+impl fmt::Debug for ThreadCounterBuilder {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ThreadCounterBuilder")
+			.field("capacity", &self.capacity)
+			.field("fair", &self.fair)
+			.field("initial_count", &self.initial_count)
+			.field("expect_zero_on_drop", &self.expect_zero_on_drop)
+			.finish_non_exhaustive()
+	}
+}
+
+impl ThreadCounter {
+	/// Returns a [`ThreadCounterBuilder`], for configuring a new counter.
+	pub fn builder() -> ThreadCounterBuilder {
+		ThreadCounterBuilder::default()
+	}
+}
@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+use crate::RawThreadCounter;
+use alloc::boxed::Box;
+use core::{
+	future::Future,
+	pin::Pin,
+	sync::atomic::Ordering,
+	task::{Context, Poll},
+};
+use futures_core::Stream;
+use tokio::sync::futures::Notified;
+
+/// A [`Stream`] of a counter's count, created by
+/// [`RawThreadCounter::changes()`].
+///
+/// This yields the current count as soon as it's first polled, and then the
+/// new count whenever it changes. Just like a `tokio::sync::watch` channel,
+/// only the latest count is observed: if the counter changes several times
+/// between polls, the intermediate counts are skipped.
+///
+/// The stream never ends on its own.
+pub struct Changes<'a> {
+	counter: &'a RawThreadCounter,
+	/// The version last yielded, if anything has been yet.
+	seen: Option<usize>,
+	/// Created before checking the version, so that changes made in between
+	/// still wake up the stream.
+	notified: Pin<Box<Notified<'a>>>,
+}
+
+impl RawThreadCounter {
+	/// Returns a [`Stream`] that yields the count each time it changes.
+	///
+	/// This is handy for reacting to the counter in asynchronous code, e.g. to
+	/// drive a progress bar, or to log spikes in activity.
+	///
+	/// # Note
+	/// While there are any streams alive, every change to the count has to
+	/// notify them, which makes incrementing and decrementing the counter a
+	/// little slower.
+	///
+	/// ```rust
+	/// use futures::StreamExt;
+	/// use thread_counter::ThreadCounter;
+	///
+	/// # #[tokio::main(flavor = "current_thread")]
+	/// # async fn main() {
+	/// let counter = ThreadCounter::default();
+	/// let mut changes = counter.changes();
+	/// assert_eq!(changes.next().await, Some(0));
+	///
+	/// let ticket = counter.ticket().unwrap();
+	/// assert_eq!(changes.next().await, Some(1));
+	/// drop(ticket);
+	/// assert_eq!(changes.next().await, Some(0));
+	/// # }
+	/// ```
+	pub fn changes(&self) -> Changes<'_> {
+		// Register before the first version is read, so that no change after
+		// that can be missed.
+		self.watchers.fetch_add(1, Ordering::SeqCst);
+		Changes {
+			counter: self,
+			seen: None,
+			notified: Box::pin(self.changes.notified()),
+		}
+	}
+
+	/// Notifies any [`Changes`] streams that the count has changed.
+	pub(crate) fn publish_change(&self) {
+		if self.watchers.load(Ordering::SeqCst) > 0 {
+			self.version.fetch_add(1, Ordering::SeqCst);
+			self.changes.notify_waiters();
+		}
+	}
+}
+
+impl Stream for Changes<'_> {
+	type Item = usize;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<usize>> {
+		let this = self.get_mut();
+		loop {
+			let version = this.counter.version.load(Ordering::SeqCst);
+			if this.seen != Some(version) {
+				this.seen = Some(version);
+				return Poll::Ready(Some(this.counter.count()));
+			}
+			match this.notified.as_mut().poll(cx) {
+				Poll::Ready(()) => this.notified.set(this.counter.changes.notified()),
+				Poll::Pending => return Poll::Pending,
+			}
+		}
+	}
+}
+
+impl Drop for Changes<'_> {
+	fn drop(&mut self) {
+		self.counter.watchers.fetch_sub(1, Ordering::SeqCst);
+	}
+}
+
+impl core::fmt::Debug for Changes<'_> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("Changes").finish_non_exhaustive()
+	}
+}
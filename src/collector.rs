@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+use crate::{CounterView, ThreadCounter};
+use alloc::{boxed::Box, format, vec::Vec};
+use core::fmt;
+use prometheus::{
+	core::{Collector, Desc},
+	proto::MetricFamily,
+	IntGauge, Registry,
+};
+
+/// A `prometheus` collector reporting a [`ThreadCounter`]'s count, peak and
+/// waiters as gauges, created by [`ThreadCounterCollector::new()`].
+///
+/// The gauges are read straight from the counter whenever the registry is
+/// scraped, so there's nothing to keep up to date in the meantime, and
+/// scraping never blocks. Most of the time, [`ThreadCounter::register()`]
+/// is all that's needed.
+///
+/// ```rust
+/// use prometheus::Registry;
+/// use thread_counter::ThreadCounter;
+///
+/// let registry = Registry::new();
+/// let counter = ThreadCounter::default();
+/// counter.register(&registry, "workers").unwrap();
+///
+/// let _ticket = counter.ticket().unwrap();
+/// let families = registry.gather();
+/// let count = families
+/// 	.iter()
+/// 	.find(|family| family.get_name() == "workers");
+/// assert_eq!(count.unwrap().get_metric()[0].get_gauge().get_value(), 1.0);
+/// ```
+pub struct ThreadCounterCollector {
+	view: CounterView,
+	count: IntGauge,
+	peak: IntGauge,
+	waiters: IntGauge,
+}
+
+impl ThreadCounterCollector {
+	/// Creates a collector for `counter`, with gauges called `name`,
+	/// `{name}_peak` and `{name}_waiters`.
+	///
+	/// # Arguments
+	/// * `counter` - The counter to report.
+	/// * `name` - The name of the count's gauge, which the others are named
+	///   after.
+	///
+	/// # Returns
+	/// * `Ok` with the new collector.
+	/// * `Err` if `name` isn't a valid metric name.
+	pub fn new(counter: &ThreadCounter, name: &str) -> prometheus::Result<Self> {
+		Ok(Self {
+			view: counter.view(),
+			count: IntGauge::new(name, "The number of active operations.")?,
+			peak: IntGauge::new(
+				format!("{name}_peak"),
+				"The highest number of active operations so far.",
+			)?,
+			waiters: IntGauge::new(
+				format!("{name}_waiters"),
+				"The number of threads waiting for the operations to finish.",
+			)?,
+		})
+	}
+}
+
+impl Collector for ThreadCounterCollector {
+	fn desc(&self) -> Vec<&Desc> {
+		[&self.count, &self.peak, &self.waiters]
+			.into_iter()
+			.flat_map(|gauge| gauge.desc())
+			.collect()
+	}
+
+	fn collect(&self) -> Vec<MetricFamily> {
+		for (gauge, value) in [
+			(&self.count, self.view.count()),
+			(&self.peak, self.view.peak()),
+			(&self.waiters, self.view.waiters()),
+		] {
+			gauge.set(i64::try_from(value).unwrap_or(i64::MAX));
+		}
+		[&self.count, &self.peak, &self.waiters]
+			.into_iter()
+			.flat_map(|gauge| gauge.collect())
+			.collect()
+	}
+}
+
+impl fmt::Debug for ThreadCounterCollector {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ThreadCounterCollector")
+			.field("name", &self.count.desc()[0].fq_name)
+			.field("view", &self.view)
+			.finish()
+	}
+}
+
+impl ThreadCounter {
+	/// Registers this counter's count, peak and waiters with a `prometheus`
+	/// registry, as gauges called `name`, `{name}_peak` and `{name}_waiters`.
+	///
+	/// See [`ThreadCounterCollector`].
+	///
+	/// # Returns
+	/// * `Ok` if the gauges were registered.
+	/// * `Err` if `name` isn't a valid metric name, or if metrics with the same
+	///   names are already registered.
+	pub fn register(&self, registry: &Registry, name: &str) -> prometheus::Result<()> {
+		registry.register(Box::new(ThreadCounterCollector::new(self, name)?))
+	}
+}
@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+use crate::{RawThreadCounter, ThreadCounter, WaitResult};
+use core::time::Duration;
+
+/// The core operations of a thread counter, for writing code that's generic
+/// over [`ThreadCounter`] and other counter types.
+///
+/// This is deliberately kept small, and is object-safe, so libraries can
+/// accept a `&dyn Counter`, and tests can substitute their own
+/// implementation, such as one that does nothing at all.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use thread_counter::{Counter, ThreadCounter, WaitResult};
+///
+/// fn run_job(counter: &dyn Counter) {
+/// 	counter.increment();
+/// 	// ...
+/// 	counter.decrement();
+/// }
+///
+/// /// A counter that ignores everything, for tests that don't care.
+/// struct NoopCounter;
+///
+/// impl Counter for NoopCounter {
+/// 	fn increment(&self) {}
+///
+/// 	fn decrement(&self) {}
+///
+/// 	fn count(&self) -> usize {
+/// 		0
+/// 	}
+///
+/// 	fn wait(&self, _timeout: Option<Duration>) -> WaitResult {
+/// 		WaitResult::Completed
+/// 	}
+/// }
+///
+/// let counter = ThreadCounter::default();
+/// run_job(&counter);
+/// assert_eq!(counter.count(), 0);
+/// run_job(&NoopCounter);
+/// ```
+pub trait Counter {
+	/// Increments the count by one.
+	///
+	/// See [`RawThreadCounter::increment()`].
+	fn increment(&self);
+
+	/// Decrements the count by one.
+	///
+	/// See [`RawThreadCounter::decrement()`].
+	fn decrement(&self);
+
+	/// Returns the current count.
+	///
+	/// See [`RawThreadCounter::count()`].
+	fn count(&self) -> usize;
+
+	/// Waits for the count to reach zero, or for the timeout to pass, if
+	/// there is one.
+	///
+	/// See [`RawThreadCounter::wait()`].
+	///
+	/// # Note
+	/// Without the `std` feature, there's no clock to measure a timeout
+	/// with, so the implementations in this crate treat any timeout like
+	/// [`Duration::ZERO`]: they check the count once, and return
+	/// [`WaitResult::TimedOut`] if it isn't zero, rather than turning a
+	/// bounded wait into an unbounded one.
+	///
+	/// ```rust
+	/// use core::time::Duration;
+	/// use thread_counter::{Counter, ThreadCounter};
+	///
+	/// let counter = ThreadCounter::with_count(1);
+	/// let timeout = Some(Duration::from_millis(10));
+	/// assert!(Counter::wait(&counter, timeout).is_timed_out());
+	/// ```
+	fn wait(&self, timeout: Option<Duration>) -> WaitResult;
+}
+
+impl Counter for RawThreadCounter {
+	fn increment(&self) {
+		RawThreadCounter::increment(self);
+	}
+
+	fn decrement(&self) {
+		RawThreadCounter::decrement(self);
+	}
+
+	fn count(&self) -> usize {
+		RawThreadCounter::count(self)
+	}
+
+	fn wait(&self, timeout: Option<Duration>) -> WaitResult {
+		#[cfg(feature = "std")]
+		return RawThreadCounter::wait(self, timeout);
+		#[cfg(not(feature = "std"))]
+		match (timeout, self.try_wait()) {
+			(None, _) => self.wait_indefinitely(),
+			(Some(_), true) => WaitResult::Completed,
+			(Some(_), false) => WaitResult::TimedOut,
+		}
+	}
+}
+
+impl Counter for ThreadCounter {
+	fn increment(&self) {
+		self.inner.increment();
+	}
+
+	fn decrement(&self) {
+		self.inner.decrement();
+	}
+
+	fn count(&self) -> usize {
+		self.inner.count()
+	}
+
+	fn wait(&self, timeout: Option<Duration>) -> WaitResult {
+		Counter::wait(&*self.inner, timeout)
+	}
+}
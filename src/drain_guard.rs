@@ -0,0 +1,106 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+use crate::ThreadCounter;
+use core::{fmt, time::Duration};
+use std::thread;
+
+/// A guard that blocks until its counter drains when dropped, created by
+/// [`ThreadCounter::drain_guard()`].
+///
+/// This is for enforcing ordering during teardown: holding one in a struct
+/// makes sure the struct isn't done being dropped until every operation
+/// it spawned has finished. As struct fields are dropped in the order they
+/// are declared, the guard should come before any fields that those
+/// operations still rely on.
+///
+/// ```rust
+/// use std::{thread, time::Duration};
+/// use thread_counter::{DrainGuard, ThreadCounter};
+///
+/// struct Pool {
+/// 	_guard: DrainGuard,
+/// 	counter: ThreadCounter,
+/// }
+///
+/// impl Pool {
+/// 	fn spawn(&self) {
+/// 		let ticket = self.counter.ticket().unwrap();
+/// 		thread::spawn(move || {
+/// 			let _ticket = ticket;
+/// 			thread::sleep(Duration::from_millis(10));
+/// 		});
+/// 	}
+/// }
+///
+/// let counter = ThreadCounter::default();
+/// let pool = Pool {
+/// 	_guard: counter.drain_guard(Duration::from_secs(5)),
+/// 	counter: counter.clone(),
+/// };
+/// pool.spawn();
+/// drop(pool); // Blocks until the spawned thread is done.
+/// assert!(counter.is_empty());
+/// ```
+///
+/// # Unwinding
+/// If the guard is dropped while its thread is already panicking, it doesn't
+/// wait at all. The operations it would be waiting for may well never finish
+/// while unwinding, such as when the panicking thread holds one of their
+/// tickets further up the stack, and waiting for them without a timeout would
+/// then hang rather than let the panic propagate.
+///
+/// # Warning
+/// Without a timeout, dropping the guard while the current thread still holds
+/// a ticket from the same counter deadlocks, as that ticket can't be dropped
+/// until the guard is done waiting.
+#[must_use = "dropping the DrainGuard immediately waits for the counter to drain"]
+pub struct DrainGuard {
+	counter: ThreadCounter,
+	timeout: Option<Duration>,
+}
+
+impl DrainGuard {
+	/// Creates a guard that waits for `counter` to drain when dropped.
+	///
+	/// # Arguments
+	/// * `counter` - The counter to wait on.
+	/// * `timeout` - An optional duration to wait when dropped. If `None`,
+	///   waits indefinitely.
+	pub fn new(counter: ThreadCounter, timeout: impl Into<Option<Duration>>) -> Self {
+		Self {
+			counter,
+			timeout: timeout.into(),
+		}
+	}
+
+	/// Returns the counter this guard waits on.
+	pub fn counter(&self) -> &ThreadCounter {
+		&self.counter
+	}
+}
+
+impl Drop for DrainGuard {
+	fn drop(&mut self) {
+		if !thread::panicking() {
+			self.counter.wait(self.timeout);
+		}
+	}
+}
+
+impl fmt::Debug for DrainGuard {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("DrainGuard")
+			.field("count", &self.counter.count())
+			.field("timeout", &self.timeout)
+			.finish()
+	}
+}
+
+impl ThreadCounter {
+	/// Returns a [`DrainGuard`], which waits for this counter to drain when
+	/// dropped.
+	///
+	/// See [`DrainGuard::new()`].
+	pub fn drain_guard(&self, timeout: impl Into<Option<Duration>>) -> DrainGuard {
+		DrainGuard::new(self.clone(), timeout)
+	}
+}
@@ -0,0 +1,204 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+use crate::WaitResult;
+use core::{
+	fmt,
+	// `atomic_wait` only works with the standard library's atomics, so these
+	// can't be swapped out for loom's.
+	sync::atomic::{AtomicU32, Ordering},
+};
+
+/// A thread counter that waits on the count itself, using futex-style
+/// atomic waits, rather than a mutex and condition variable.
+///
+/// This has the same core API as [`RawThreadCounter`](crate::RawThreadCounter),
+/// but takes up just 8 bytes, and waiting and waking each go straight to the
+/// operating system in a single syscall, with nothing else to lock along the
+/// way. It's a good fit for huge numbers of counters, or for `static`s.
+///
+/// # Note
+/// Atomic waits can't time out, so there's no way to wait with a timeout,
+/// and none of the extras of the regular counters, such as capacities,
+/// callbacks or closing. For those, use a
+/// [`ThreadCounter`](crate::ThreadCounter) instead.
+///
+/// The count is a [`u32`] rather than a [`usize`], as that's all atomic waits
+/// support, and saturates at [`u32::MAX`].
+///
+/// ```rust
+/// use std::thread;
+/// use thread_counter::AtomicWaitCounter;
+///
+/// static COUNTER: AtomicWaitCounter = AtomicWaitCounter::new();
+///
+/// for _ in 0..4 {
+/// 	let ticket = COUNTER.ticket();
+/// 	thread::spawn(move || {
+/// 		// Do some work, holding onto the ticket.
+/// 		drop(ticket);
+/// 	});
+/// }
+/// assert!(COUNTER.wait_indefinitely().is_completed());
+/// assert!(COUNTER.is_empty());
+/// ```
+#[derive(Default)]
+pub struct AtomicWaitCounter {
+	/// The current number of active threads or operations, which waiting
+	/// threads wait on directly.
+	count: AtomicU32,
+	/// The number of threads currently waiting for `count` to reach zero, so
+	/// that dropping it to zero only needs to wake them up if there are any.
+	waiters: AtomicU32,
+}
+
+impl AtomicWaitCounter {
+	/// Creates a new counter, starting at zero.
+	pub const fn new() -> Self {
+		Self::with_count(0)
+	}
+
+	/// Creates a new counter, starting at `count` rather than zero.
+	pub const fn with_count(count: u32) -> Self {
+		Self {
+			count: AtomicU32::new(count),
+			waiters: AtomicU32::new(0),
+		}
+	}
+
+	/// Takes a new [`AtomicWaitTicket`], which decrements the count again when
+	/// dropped.
+	#[must_use = "dropping the AtomicWaitTicket immediately decrements the counter"]
+	pub fn ticket(&self) -> AtomicWaitTicket<'_> {
+		self.increment();
+		AtomicWaitTicket { counter: self }
+	}
+
+	/// Increments the counter.
+	///
+	/// See [`RawThreadCounter::increment()`](crate::RawThreadCounter::increment()).
+	pub fn increment(&self) {
+		self.increment_by(1);
+	}
+
+	/// Increments the counter by `n` at once, saturating at [`u32::MAX`].
+	///
+	/// See [`RawThreadCounter::increment_by()`](crate::RawThreadCounter::increment_by()).
+	pub fn increment_by(&self, n: u32) {
+		self.update(|count| count.saturating_add(n));
+	}
+
+	/// Decrements the counter, waking up any waiting threads if it reaches
+	/// zero.
+	///
+	/// See [`RawThreadCounter::decrement()`](crate::RawThreadCounter::decrement()).
+	pub fn decrement(&self) {
+		self.decrement_by(1);
+	}
+
+	/// Decrements the counter by `n` at once, saturating at zero, and waking
+	/// up any waiting threads if it reaches zero.
+	///
+	/// See [`RawThreadCounter::decrement_by()`](crate::RawThreadCounter::decrement_by()).
+	pub fn decrement_by(&self, n: u32) {
+		let previous = self.update(|count| count.saturating_sub(n));
+		if previous > 0 && previous <= n && self.waiters.load(Ordering::SeqCst) > 0 {
+			atomic_wait::wake_all(&self.count);
+		}
+	}
+
+	/// Returns the current value of the counter.
+	///
+	/// Just like [`RawThreadCounter::count()`](crate::RawThreadCounter::count()),
+	/// this is only a snapshot, and may already be stale by the time it is
+	/// returned.
+	pub fn count(&self) -> u32 {
+		self.count.load(Ordering::SeqCst)
+	}
+
+	/// Returns `true` if the count is currently zero.
+	pub fn is_empty(&self) -> bool {
+		self.count() == 0
+	}
+
+	/// Returns the number of threads currently waiting for the count to reach
+	/// zero.
+	pub fn waiters(&self) -> usize {
+		self.waiters.load(Ordering::SeqCst) as usize
+	}
+
+	/// Returns `true` if the count is currently zero, without blocking.
+	///
+	/// See [`RawThreadCounter::try_wait()`](crate::RawThreadCounter::try_wait()).
+	pub fn try_wait(&self) -> bool {
+		self.is_empty()
+	}
+
+	/// Waits for the counter to reach zero.
+	///
+	/// Just like with the regular counters, a count that only drops to zero
+	/// very briefly, before being incremented again, may be missed.
+	///
+	/// # Returns
+	/// * [`WaitResult::Completed`], once the count reached zero. As there's no
+	///   way to interrupt the wait, that's the only possible result, but it's
+	///   still returned for symmetry with
+	///   [`RawThreadCounter::wait_indefinitely()`](crate::RawThreadCounter::wait_indefinitely()).
+	pub fn wait_indefinitely(&self) -> WaitResult {
+		if self.is_empty() {
+			return WaitResult::Completed;
+		}
+		// Register before checking the count, so that a thread dropping it to
+		// zero afterwards is guaranteed to see us and wake us up. Waiting on a
+		// value the count no longer has returns straight away, so a wake-up
+		// between checking the count and waiting on it can't be missed either.
+		self.waiters.fetch_add(1, Ordering::SeqCst);
+		loop {
+			let count = self.count();
+			if count == 0 {
+				break;
+			}
+			atomic_wait::wait(&self.count, count);
+		}
+		self.waiters.fetch_sub(1, Ordering::SeqCst);
+		WaitResult::Completed
+	}
+
+	/// Applies `f` to the count, returning the previous count.
+	fn update(&self, f: impl Fn(u32) -> u32) -> u32 {
+		self.count
+			.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| Some(f(count)))
+			.unwrap_or_else(|count| count)
+	}
+}
+
+impl fmt::Debug for AtomicWaitCounter {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("AtomicWaitCounter")
+			.field("count", &self.count())
+			.field("waiters", &self.waiters())
+			.finish()
+	}
+}
+
+/// A RAII guard for an [`AtomicWaitCounter`], created by
+/// [`AtomicWaitCounter::ticket()`].
+///
+/// This decrements the counter when dropped, just like a regular
+/// [`Ticket`](crate::Ticket). It borrows the counter rather than sharing
+/// ownership of it, so use scoped threads, or a `static` counter, to hand
+/// tickets to other threads.
+#[must_use = "dropping the AtomicWaitTicket immediately decrements the counter"]
+pub struct AtomicWaitTicket<'a> {
+	counter: &'a AtomicWaitCounter,
+}
+
+impl Drop for AtomicWaitTicket<'_> {
+	fn drop(&mut self) {
+		self.counter.decrement();
+	}
+}
+
+impl fmt::Debug for AtomicWaitTicket<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("AtomicWaitTicket").finish_non_exhaustive()
+	}
+}
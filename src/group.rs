@@ -0,0 +1,258 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+#[cfg(feature = "std")]
+use crate::Ticket;
+use crate::{sync::Instant, ThreadCounter, WaitResult};
+#[cfg(feature = "std")]
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::time::Duration;
+
+/// A group of [`ThreadCounter`]s that can be waited on as one.
+///
+/// This is useful when several subsystems each have their own counter, but
+/// shutting down requires all of them to drain.
+///
+/// ```rust
+/// # #[cfg(feature = "std")]
+/// # {
+/// use std::{thread, time::Duration};
+/// use thread_counter::{CounterGroup, ThreadCounter};
+///
+/// let network = ThreadCounter::default();
+/// let storage = ThreadCounter::default();
+/// let group: CounterGroup = [network.clone(), storage.clone()].into_iter().collect();
+///
+/// for counter in [&network, &storage] {
+/// 	let ticket = counter.ticket().unwrap();
+/// 	thread::spawn(move || {
+/// 		// Do some work, holding onto the ticket.
+/// 		drop(ticket);
+/// 	});
+/// }
+///
+/// assert!(group.wait(Duration::from_secs(5)).is_completed());
+/// assert_eq!(group.total_count(), 0);
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CounterGroup {
+	counters: Vec<ThreadCounter>,
+}
+
+impl CounterGroup {
+	/// Adds a counter to the group.
+	pub fn push(&mut self, counter: ThreadCounter) {
+		self.counters.push(counter);
+	}
+
+	/// Returns the counters in this group.
+	pub fn counters(&self) -> &[ThreadCounter] {
+		&self.counters
+	}
+
+	/// Returns the sum of the counts of every counter in the group.
+	///
+	/// # Note
+	/// Each count is read separately, so this isn't an atomic snapshot of the
+	/// whole group.
+	pub fn total_count(&self) -> usize {
+		self.counters.iter().map(|counter| counter.count()).sum()
+	}
+
+	/// Waits for every counter in the group to reach zero, with an optional
+	/// timeout.
+	///
+	/// The timeout is a single budget for the whole group, rather than for
+	/// each counter. As counters can be incremented again after they've been
+	/// waited on, this only returns once every counter has been seen at zero
+	/// in a single pass over the group.
+	///
+	/// # Arguments
+	/// * `timeout` - An optional duration to wait. If `None`, waits
+	///   indefinitely.
+	///
+	/// # Returns
+	/// * [`WaitResult::Completed`] if every counter reached zero.
+	/// * [`WaitResult::TimedOut`] if the timeout was reached before every
+	///   counter reached zero.
+	/// * [`WaitResult::Interrupted`] if waiting on any counter was cut short by
+	///   [`RawThreadCounter::notify_all()`](crate::RawThreadCounter::notify_all).
+	#[cfg(feature = "std")]
+	pub fn wait(&self, timeout: impl Into<Option<Duration>>) -> WaitResult {
+		self.wait_until(crate::deadline_after(timeout.into()))
+	}
+
+	/// Waits for every counter in the group to reach zero, without any
+	/// timeout.
+	///
+	/// This is equivalent to `wait(None)`, but is also available without the
+	/// `std` feature, in which case it busy-waits.
+	pub fn wait_indefinitely(&self) -> WaitResult {
+		self.wait_until(None)
+	}
+
+	/// Waits for any one counter in the group to reach zero, with an optional
+	/// timeout.
+	///
+	/// Each counter has its own condition variable, so there's nothing to
+	/// block on for the whole group at once. Instead, this takes turns waiting
+	/// on each busy counter for a short slice of time, up to a millisecond.
+	/// That means a drain is noticed within a millisecond or so per busy
+	/// counter, rather than instantly, and that a counter which drains only
+	/// briefly, while another is being waited on, may be missed.
+	///
+	/// # Arguments
+	/// * `timeout` - An optional duration to wait. If `None`, waits
+	///   indefinitely.
+	///
+	/// # Returns
+	/// * `Some` with the index of the first counter found at zero.
+	/// * `None` if the timeout was reached first, if waiting was cut short by
+	///   [`RawThreadCounter::notify_all()`](crate::RawThreadCounter::notify_all),
+	///   or if the group is empty.
+	///
+	/// ```rust
+	/// use std::time::Duration;
+	/// use thread_counter::{CounterGroup, ThreadCounter};
+	///
+	/// let busy = ThreadCounter::with_count(1);
+	/// let idle = ThreadCounter::default();
+	/// let group: CounterGroup = [busy, idle].into_iter().collect();
+	/// assert_eq!(group.wait_any(Duration::from_secs(5)), Some(1));
+	/// ```
+	#[cfg(feature = "std")]
+	pub fn wait_any(&self, timeout: impl Into<Option<Duration>>) -> Option<usize> {
+		const SLICE: Duration = Duration::from_millis(1);
+
+		if self.counters.is_empty() {
+			return None;
+		}
+		let deadline = crate::deadline_after(timeout.into());
+		loop {
+			if let Some(index) = self.counters.iter().position(|counter| counter.is_empty()) {
+				return Some(index);
+			}
+			for (index, counter) in self.counters.iter().enumerate() {
+				let slice = Instant::now() + SLICE;
+				let slice = deadline.map_or(slice, |deadline| deadline.min(slice));
+				match counter.block_while(|count| count > 0, Some(slice)).0 {
+					WaitResult::Completed => return Some(index),
+					WaitResult::Interrupted => return None,
+					WaitResult::TimedOut => {}
+				}
+				if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+					return None;
+				}
+			}
+		}
+	}
+
+	/// Waits for every counter in the group to reach zero, or until
+	/// `deadline` passes.
+	fn wait_until(&self, deadline: Option<Instant>) -> WaitResult {
+		loop {
+			let mut waited = false;
+			for counter in &self.counters {
+				if counter.is_empty() {
+					continue;
+				}
+				waited = true;
+				let (result, _) = counter.block_while(|count| count > 0, deadline);
+				if !result.is_completed() {
+					return result;
+				}
+			}
+			if !waited {
+				return WaitResult::Completed;
+			}
+		}
+	}
+}
+
+/// Waits for the counters of all of the given tickets to drain, with one
+/// shared timeout.
+///
+/// This is for aggregating tickets taken from several subsystems, each with
+/// their own counter. Tickets from the same counter are grouped together, so
+/// each counter is only waited on once.
+///
+/// As the given tickets keep their counters from reaching zero, this waits
+/// for each counter to drop to just the count held by those tickets, i.e. for
+/// every other operation on it to finish. Just like [`CounterGroup::wait()`],
+/// this only returns once every counter has been seen there in a single pass.
+///
+/// # Arguments
+/// * `tickets` - The tickets whose counters to wait on.
+/// * `timeout` - An optional duration to wait. If `None`, waits indefinitely.
+///
+/// # Returns
+/// * [`WaitResult::Completed`] if every counter drained down to the given
+///   tickets.
+/// * [`WaitResult::TimedOut`] if the timeout was reached first.
+/// * [`WaitResult::Interrupted`] if waiting on any counter was cut short by
+///   [`RawThreadCounter::notify_all()`](crate::RawThreadCounter::notify_all).
+///
+/// ```rust
+/// use std::{thread, time::Duration};
+/// use thread_counter::{wait_all, ThreadCounter};
+///
+/// let network = ThreadCounter::default();
+/// let storage = ThreadCounter::default();
+/// let tickets = [network.ticket().unwrap(), storage.ticket().unwrap()];
+///
+/// for counter in [&network, &storage] {
+/// 	let ticket = counter.ticket().unwrap();
+/// 	thread::spawn(move || {
+/// 		// Do some work, holding onto the ticket.
+/// 		drop(ticket);
+/// 	});
+/// }
+///
+/// assert!(wait_all(&tickets, Duration::from_secs(5)).is_completed());
+/// assert_eq!(network.count() + storage.count(), 2);
+/// ```
+#[cfg(feature = "std")]
+pub fn wait_all(tickets: &[Ticket], timeout: impl Into<Option<Duration>>) -> WaitResult {
+	let mut counters: Vec<(&ThreadCounter, usize)> = Vec::new();
+	for ticket in tickets {
+		match counters
+			.iter_mut()
+			.find(|(counter, _)| Arc::ptr_eq(&counter.inner, &ticket.counter.inner))
+		{
+			Some((_, held)) => *held += ticket.count,
+			None => counters.push((&ticket.counter, ticket.count)),
+		}
+	}
+	let deadline = crate::deadline_after(timeout.into());
+	loop {
+		let mut waited = false;
+		for &(counter, held) in &counters {
+			if counter.count() <= held {
+				continue;
+			}
+			waited = true;
+			let (result, _) = counter.block_while(|count| count > held, deadline);
+			if !result.is_completed() {
+				return result;
+			}
+		}
+		if !waited {
+			return WaitResult::Completed;
+		}
+	}
+}
+
+impl FromIterator<ThreadCounter> for CounterGroup {
+	fn from_iter<I: IntoIterator<Item = ThreadCounter>>(iter: I) -> Self {
+		Self {
+			counters: iter.into_iter().collect(),
+		}
+	}
+}
+
+impl Extend<ThreadCounter> for CounterGroup {
+	fn extend<I: IntoIterator<Item = ThreadCounter>>(&mut self, iter: I) {
+		self.counters.extend(iter);
+	}
+}
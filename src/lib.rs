@@ -12,12 +12,28 @@
 //! - Thread-safe counting of active operations.
 //! - RAII-based automatic decrementing using [`Ticket`]s.
 //! - Ability to wait for all operations to complete, with optional timeout.
+//! - Asynchronous waiting and change streams, with the `async` feature.
+//! - Cancelling asynchronous waits with a `CancellationToken`, with the
+//!   `tokio-util` feature.
+//! - Serializable snapshots of counters, with the `serde` feature.
+//! - Reporting the count as a gauge, with the `metrics` feature.
+//! - Collecting the count, peak and waiters into a `prometheus` registry, with
+//!   the `prometheus` feature.
+//! - Tracing events and per-ticket spans, with the `tracing` feature.
+//! - Locking with `parking_lot`, or with the standard library by disabling the
+//!   default `parking_lot` feature.
+//! - A leaner counter waiting with futex-style atomic waits, with the
+//!   `atomic-wait` feature.
+//! - `no_std` support, by disabling the default `std` feature. Waiting then
+//!   busy-waits, and timeouts are unavailable.
 //!
 //! ## Usage
 //!
 //! Here's a basic example of how to use the [`ThreadCounter`]:
 //!
 //! ```rust
+//! # #[cfg(feature = "std")]
+//! # {
 //! use std::{thread, time::Duration};
 //! use thread_counter::ThreadCounter;
 //!
@@ -25,9 +41,10 @@
 //!
 //! // Spawn some threads
 //! for _ in 0..5 {
+//! 	let counter = counter.clone();
 //! 	thread::spawn(move || {
 //! 		// Take a ticket, incrementing the counter.
-//! 		let ticket = counter.ticket();
+//! 		let _ticket = counter.ticket().unwrap();
 //! 		// Simulate some work
 //! 		thread::sleep(Duration::from_millis(100));
 //! 		// `ticket` is automatically dropped here, decrementing the counter
@@ -37,8 +54,10 @@
 //! // Wait for all threads to complete, timing out after 200ms.
 //! counter.wait(Duration::from_millis(200));
 //! println!("All threads have completed!");
+//! # }
 //! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![forbid(unsafe_code)]
 #![warn(
 	clippy::correctness,
@@ -49,8 +68,142 @@
 )]
 #![allow(clippy::tabs_in_doc_comments)]
 
-use parking_lot::{Condvar, Mutex};
-use std::{ops::Deref, sync::Arc, time::Duration};
+extern crate alloc;
+
+mod builder;
+#[cfg(feature = "async")]
+mod changes;
+#[cfg(feature = "prometheus")]
+mod collector;
+mod counter;
+#[cfg(feature = "std")]
+mod drain_guard;
+#[cfg(feature = "atomic-wait")]
+mod futex;
+mod group;
+#[cfg(feature = "std")]
+mod sharded;
+mod snapshot;
+mod sync;
+#[cfg(feature = "std")]
+mod timed;
+mod track;
+mod view;
+#[cfg(feature = "std")]
+mod wait_builder;
+mod wait_group;
+mod weak;
+
+pub use builder::ThreadCounterBuilder;
+#[cfg(feature = "async")]
+pub use changes::Changes;
+#[cfg(feature = "prometheus")]
+pub use collector::ThreadCounterCollector;
+pub use counter::Counter;
+#[cfg(feature = "std")]
+pub use drain_guard::DrainGuard;
+#[cfg(feature = "atomic-wait")]
+pub use futex::{AtomicWaitCounter, AtomicWaitTicket};
+#[cfg(feature = "std")]
+pub use group::wait_all;
+pub use group::CounterGroup;
+#[cfg(feature = "std")]
+pub use sharded::{ShardedThreadCounter, ShardedTicket};
+pub use snapshot::CounterSnapshot;
+#[cfg(feature = "std")]
+pub use timed::TimedTicket;
+pub use track::Track;
+pub use view::CounterView;
+#[cfg(feature = "std")]
+pub use wait_builder::WaitBuilder;
+pub use wait_group::WaitGroup;
+pub use weak::WeakCounter;
+
+use crate::sync::{
+	atomic::{self, AtomicBool, AtomicU64, AtomicUsize, Ordering},
+	Condvar, Instant, Mutex, MutexGuard,
+};
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+#[cfg(feature = "async")]
+use core::task::{Context, Poll, Waker};
+#[cfg(feature = "std")]
+use core::time::Duration;
+use core::{
+	fmt,
+	hash::{Hash, Hasher},
+	num::NonZeroUsize,
+	ops::{Deref, DerefMut},
+};
+
+/// The bit of [`RawThreadCounter`]'s count word that's set once the counter
+/// has been [closed](RawThreadCounter::close()).
+///
+/// Keeping this in the same word as the count means that a ticket can be
+/// refused atomically, as part of the very update that would have taken it,
+/// rather than being handed out and taken back again.
+const CLOSED: usize = 1 << (usize::BITS - 1);
+
+/// The highest the count can go, as the top bit is taken by [`CLOSED`].
+const MAX_COUNT: usize = !CLOSED;
+
+/// Expands to a new, empty [`RawThreadCounter`].
+///
+/// This is shared between the `const` and loom versions of
+/// [`RawThreadCounter::new()`].
+macro_rules! empty_counter {
+	() => {
+		RawThreadCounter {
+			count: AtomicUsize::new(0),
+			peak: AtomicUsize::new(0),
+			generation: AtomicU64::new(0),
+			delta_base: AtomicUsize::new(0),
+			lock: Mutex::new(()),
+			condvar: Condvar::new(),
+			waiters: AtomicUsize::new(0),
+			interrupts: AtomicUsize::new(0),
+			#[cfg(feature = "async")]
+			drained: tokio::sync::Notify::const_new(),
+			#[cfg(feature = "async")]
+			wakers: Mutex::new(Vec::new()),
+			#[cfg(feature = "async")]
+			pending_wakers: AtomicUsize::new(0),
+			#[cfg(feature = "async")]
+			changes: tokio::sync::Notify::const_new(),
+			#[cfg(feature = "async")]
+			version: AtomicUsize::new(0),
+			#[cfg(feature = "async")]
+			watchers: AtomicUsize::new(0),
+			capacity: None,
+			fair: false,
+			next_turn: AtomicUsize::new(0),
+			now_serving: AtomicUsize::new(0),
+			skipped_turns: Mutex::new(Vec::new()),
+			drain_callbacks: Mutex::new(Vec::new()),
+			drain_callback_count: AtomicUsize::new(0),
+			activate_callbacks: Mutex::new(Vec::new()),
+			activate_callback_count: AtomicUsize::new(0),
+			#[cfg(feature = "std")]
+			subscribers: Mutex::new(Vec::new()),
+			#[cfg(feature = "std")]
+			subscriber_count: AtomicUsize::new(0),
+			leak_callback: None,
+			high_water: None,
+			expect_zero_on_drop: false,
+			#[cfg(feature = "metrics")]
+			gauge: None,
+			#[cfg(feature = "std")]
+			track_idle: false,
+			#[cfg(feature = "std")]
+			last_change: AtomicU64::new(0),
+			#[cfg(feature = "std")]
+			panicked: AtomicUsize::new(0),
+			#[cfg(feature = "std")]
+			panic_callbacks: Mutex::new(Vec::new()),
+			#[cfg(feature = "std")]
+			slow_callback: None,
+		}
+	};
+}
 
 /// A thread-safe counter for tracking the number of active threads or
 /// operations.
@@ -64,18 +217,296 @@ pub struct ThreadCounter {
 }
 
 impl ThreadCounter {
+	/// Creates a new thread counter which allows at most `max` tickets to be
+	/// held at once.
+	///
+	/// Once `max` tickets are held, [`ThreadCounter::ticket()`] blocks until
+	/// one of them is dropped, effectively turning the counter into a
+	/// lightweight semaphore. Counters created with [`Default`] have no
+	/// capacity limit.
+	///
+	/// # Panics
+	/// Panics if `max` is zero, as no ticket could ever be acquired.
+	///
+	/// ```rust
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::with_capacity(2);
+	/// let first = counter.ticket().unwrap();
+	/// let second = counter.ticket().unwrap();
+	/// assert_eq!(counter.count(), 2);
+	/// // Taking a third ticket here would block until one is dropped.
+	/// drop(first);
+	/// let third = counter.ticket().unwrap();
+	/// assert_eq!(counter.count(), 2);
+	/// ```
+	pub fn with_capacity(max: usize) -> Self {
+		Self::builder().capacity(max).build()
+	}
+
+	/// Creates a new thread counter, starting at `count` rather than zero.
+	///
+	/// This is handy when migrating code that already knows how many
+	/// operations are outstanding, or when seeding the counter from an
+	/// external source, such as state saved before a restart. Those operations
+	/// then have to be balanced with [`RawThreadCounter::decrement()`].
+	///
+	/// ```rust
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::with_count(2);
+	/// assert_eq!(counter.count(), 2);
+	/// counter.decrement_by(2);
+	/// assert!(counter.try_wait());
+	/// ```
+	pub fn with_count(count: usize) -> Self {
+		Self {
+			inner: Arc::new(RawThreadCounter::with_count(count)),
+		}
+	}
+
 	/// Creates a new [`Ticket`] from this thread counter.
 	///
 	/// This method increments the thread count and returns a [`Ticket`] that
 	/// will automatically decrement the count when dropped.
 	///
+	/// If the counter was created with [`ThreadCounter::with_capacity()`], this
+	/// blocks until the count is below the capacity before incrementing it.
+	///
+	/// # Returns
+	/// * `Ok` with a new [`Ticket`] instance associated with this counter.
+	/// * `Err` if the counter has been closed with
+	///   [`RawThreadCounter::close()`], in which case the count is left
+	///   untouched.
+	///
+	/// # Note
+	/// The ticket needs to be bound to a variable for as long as the work it
+	/// counts is running. Dropping it straight away, including by binding it
+	/// to `_`, decrements the counter again immediately:
+	///
+	/// ```rust
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	///
+	/// // Wrong: the ticket is dropped at the end of the statement.
+	/// let _ = counter.ticket().unwrap();
+	/// assert_eq!(counter.count(), 0);
+	///
+	/// // Right: the ticket lives until the end of the scope.
+	/// let _ticket = counter.ticket().unwrap();
+	/// assert_eq!(counter.count(), 1);
+	/// ```
+	#[must_use = "dropping the Ticket immediately decrements the counter"]
+	pub fn ticket(&self) -> Result<Ticket, Closed> {
+		self.acquire(1)?;
+		Ok(Ticket::new(self.clone(), 1))
+	}
+
+	/// Creates a new [`Ticket`] that counts as `n` tickets at once,
+	/// incrementing the count by `n`, and decrementing it by `n` again when
+	/// dropped.
+	///
+	/// This is handy for representing a chunk of work as a single guard.
+	/// Otherwise, it behaves just like [`ThreadCounter::ticket()`], blocking
+	/// until there's room for all `n` below the counter's capacity, if it has
+	/// one.
+	///
+	/// # Panics
+	/// Panics if `n` is greater than the counter's capacity, as there could
+	/// never be room for it.
+	///
+	/// # Returns
+	/// * `Ok` with a new [`Ticket`] instance associated with this counter.
+	/// * `Err` if the counter has been closed with
+	///   [`RawThreadCounter::close()`], in which case the count is left
+	///   untouched.
+	///
+	/// ```rust
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// let chunk = counter.ticket_n(3).unwrap();
+	/// assert_eq!(counter.count(), 3);
+	/// drop(chunk);
+	/// assert_eq!(counter.count(), 0);
+	/// ```
+	#[must_use = "dropping the Ticket immediately decrements the counter"]
+	pub fn ticket_n(&self, n: usize) -> Result<Ticket, Closed> {
+		self.acquire(n)?;
+		Ok(Ticket::new(self.clone(), n))
+	}
+
+	/// Consumes this handle to the counter, turning it into a new [`Ticket`].
+	///
+	/// This works just like [`ThreadCounter::ticket()`], as the returned
+	/// ticket holds its own reference to the counter either way, and so is
+	/// `'static`, and doesn't borrow from `self`. Taking `self` by value just
+	/// saves a clone, and makes it clearer in code that moves the ticket into
+	/// a thread or task that nothing else is left borrowing the counter.
+	///
+	/// # Returns
+	/// * `Ok` with a new [`Ticket`] instance associated with this counter.
+	/// * `Err` if the counter has been closed with
+	///   [`RawThreadCounter::close()`].
+	///
+	/// ```rust
+	/// use std::thread;
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// let ticket = counter.clone().owned_ticket().unwrap();
+	/// thread::spawn(move || {
+	/// 	// Do some work, then let go of the ticket.
+	/// 	drop(ticket);
+	/// })
+	/// .join()
+	/// .unwrap();
+	/// assert_eq!(counter.count(), 0);
+	/// ```
+	///
+	/// It can just as well be moved into an asynchronous task:
+	///
+	/// ```rust
+	/// # #[tokio::main(flavor = "current_thread")]
+	/// # async fn main() {
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// let ticket = counter.clone().owned_ticket().unwrap();
+	/// tokio::spawn(async move {
+	/// 	drop(ticket);
+	/// })
+	/// .await
+	/// .unwrap();
+	/// assert_eq!(counter.count(), 0);
+	/// # }
+	/// ```
+	#[must_use = "dropping the Ticket immediately decrements the counter"]
+	pub fn owned_ticket(self) -> Result<Ticket, Closed> {
+		self.acquire(1)?;
+		Ok(Ticket::new(self, 1))
+	}
+
+	/// Creates a new [`Ticket`] from this thread counter, blocking until the
+	/// count is below the counter's capacity or `deadline` passes.
+	///
+	/// This is the bounded counterpart to [`ThreadCounter::try_ticket()`],
+	/// for rate-limited pipelines that should wait a little while for a slot,
+	/// but drop the request if none frees up in time.
+	///
+	/// # Returns
+	/// * `Some` with a new [`Ticket`], if there was room before the deadline.
+	///   This is always the case for counters without a capacity.
+	/// * `None` if the deadline passed first, or the counter has been closed
+	///   with [`RawThreadCounter::close()`].
+	///
+	/// ```rust
+	/// use std::time::{Duration, Instant};
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::with_capacity(1);
+	/// let ticket = counter.ticket().unwrap();
+	/// let deadline = Instant::now() + Duration::from_millis(10);
+	/// assert!(counter.acquire_deadline(deadline).is_none());
+	/// drop(ticket);
+	/// assert!(counter.acquire_deadline(deadline).is_some());
+	/// ```
+	#[cfg(feature = "std")]
+	#[must_use = "dropping the Ticket immediately decrements the counter"]
+	pub fn acquire_deadline(&self, deadline: Instant) -> Option<Ticket> {
+		match self.acquire_until(1, Some(deadline)) {
+			Ok(true) => Some(Ticket::new(self.clone(), 1)),
+			Ok(false) | Err(_) => None,
+		}
+	}
+
+	/// Creates a new [`Ticket`] from this thread counter, unless it's at
+	/// capacity.
+	///
+	/// Unlike [`ThreadCounter::ticket()`], this never blocks, making it
+	/// suitable for load-shedding, where excess work is rejected rather than
+	/// queued.
+	///
 	/// # Returns
-	/// A new [`Ticket`] instance associated with this counter.
-	pub fn ticket(&self) -> Ticket {
-		self.increment();
-		Ticket {
-			counter: self.clone(),
+	/// * `Some` with a new [`Ticket`], if the count was below the counter's
+	///   capacity. This is always the case for counters without a capacity.
+	/// * `None` if the counter is already at capacity, or has been closed with
+	///   [`RawThreadCounter::close()`].
+	///
+	/// ```rust
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::with_capacity(1);
+	/// let ticket = counter.try_ticket().expect("counter is empty");
+	/// assert!(counter.try_ticket().is_none());
+	/// drop(ticket);
+	/// assert!(counter.try_ticket().is_some());
+	/// ```
+	#[must_use = "dropping the Ticket immediately decrements the counter"]
+	pub fn try_ticket(&self) -> Option<Ticket> {
+		self.try_acquire(1).then(|| Ticket::new(self.clone(), 1))
+	}
+
+	/// Runs `f` with this counter, then waits for the count to reach zero
+	/// before returning.
+	///
+	/// This mirrors the ergonomics of [`std::thread::scope`], making it
+	/// impossible to forget the final wait.
+	///
+	/// # Panics
+	/// If `f` panics, this still waits for the count to reach zero before the
+	/// panic continues to unwind, so that tickets handed out by the closure
+	/// never outlive the scope.
+	///
+	/// ```rust
+	/// use std::thread;
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// counter.scope(|counter| {
+	/// 	for _ in 0..5 {
+	/// 		let ticket = counter.ticket().unwrap();
+	/// 		thread::spawn(move || {
+	/// 			// Do some work, holding onto the ticket.
+	/// 			drop(ticket);
+	/// 		});
+	/// 	}
+	/// });
+	/// assert_eq!(counter.count(), 0);
+	/// ```
+	pub fn scope<R>(&self, f: impl FnOnce(&ThreadCounter) -> R) -> R {
+		struct WaitOnDrop<'a>(&'a RawThreadCounter);
+
+		impl Drop for WaitOnDrop<'_> {
+			fn drop(&mut self) {
+				// This isn't user-facing, so don't let it be interrupted.
+				while !self.0.wait_indefinitely().is_completed() {}
+			}
 		}
+
+		let _wait = WaitOnDrop(self);
+		f(self)
+	}
+
+	/// Creates a new [`DataTicket`] from this thread counter, carrying `data`
+	/// along with it.
+	///
+	/// This behaves just like [`ThreadCounter::ticket()`], but ties some
+	/// per-operation data (such as a task ID or start time) to the lifetime of
+	/// the ticket.
+	///
+	/// # Returns
+	/// * `Ok` with a new [`DataTicket`] instance associated with this counter,
+	///   which dereferences to `data`.
+	/// * `Err` if the counter has been closed with
+	///   [`RawThreadCounter::close()`].
+	#[must_use = "dropping the DataTicket immediately decrements the counter"]
+	pub fn ticket_with<T>(&self, data: T) -> Result<DataTicket<T>, Closed> {
+		Ok(DataTicket {
+			ticket: self.ticket()?,
+			data,
+		})
 	}
 }
 
@@ -87,92 +518,2886 @@ impl Deref for ThreadCounter {
 	}
 }
 
+impl fmt::Debug for ThreadCounter {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.inner
+			.debug_fields(&mut f.debug_struct("ThreadCounter"))
+	}
+}
+
+/// Formats a human-readable status line, such as `ThreadCounter(3 active)`.
+///
+/// Reading the count never takes a lock, so formatting never blocks.
+impl fmt::Display for ThreadCounter {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "ThreadCounter({} active)", self.count())
+	}
+}
+
 impl AsRef<RawThreadCounter> for ThreadCounter {
 	fn as_ref(&self) -> &RawThreadCounter {
 		&self.inner
 	}
 }
 
+/// Counters are compared by identity, not by their count: clones of the same
+/// counter are equal, while separately created counters never are, even if
+/// their counts match.
+///
+/// ```rust
+/// use std::collections::HashMap;
+/// use thread_counter::ThreadCounter;
+///
+/// let network = ThreadCounter::default();
+/// let storage = ThreadCounter::default();
+/// assert_eq!(network, network.clone());
+/// assert_ne!(network, storage);
+///
+/// let mut names = HashMap::new();
+/// names.insert(network.clone(), "network");
+/// names.insert(storage.clone(), "storage");
+/// assert_eq!(names[&network], "network");
+/// ```
+impl PartialEq for ThreadCounter {
+	fn eq(&self, other: &Self) -> bool {
+		Arc::ptr_eq(&self.inner, &other.inner)
+	}
+}
+
+impl Eq for ThreadCounter {}
+
+/// Hashes the counter's identity, consistently with its [`PartialEq`]
+/// implementation.
+impl Hash for ThreadCounter {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		Arc::as_ptr(&self.inner).hash(state);
+	}
+}
+
 /// The internal implementation of the thread counter.
 ///
 /// This struct handles the actual counting and synchronization mechanisms.
+///
+/// The count itself is an atomic, so incrementing and decrementing it doesn't
+/// take a lock on its own. Locks are only taken when there's someone to tell
+/// about the change: threads waiting on the counter, or, when the count drops
+/// to or rises from zero, any callbacks, channels or tasks registered to find
+/// out about that.
 pub struct RawThreadCounter {
-	count: Mutex<usize>,
+	/// The current number of active threads or operations.
+	count: AtomicUsize,
+	/// The highest `count` has been since the last peak reset.
+	peak: AtomicUsize,
+	/// The number of times `count` has gone from zero to nonzero.
+	generation: AtomicU64,
+	/// The count as of the last call to [`RawThreadCounter::take_delta()`].
+	delta_base: AtomicUsize,
+	/// Held by waiting threads while they check their condition, so that they
+	/// can't miss a notification before parking on `condvar`. It doesn't
+	/// protect any data itself.
+	lock: Mutex<()>,
 	condvar: Condvar,
+	/// The number of threads currently blocked waiting on `condvar`.
+	///
+	/// Waiting threads register themselves here before checking their
+	/// condition, so a thread changing the count only needs to notify
+	/// `condvar` if this is nonzero afterwards.
+	waiters: AtomicUsize,
+	/// Incremented by [`RawThreadCounter::notify_all()`], so that waiting
+	/// threads can tell they've been interrupted.
+	interrupts: AtomicUsize,
+	/// Wakes up asynchronous waiters when the count reaches zero.
+	#[cfg(feature = "async")]
+	drained: tokio::sync::Notify,
+	/// Wakes up tasks polling [`RawThreadCounter::poll_wait()`] when the count
+	/// reaches zero.
+	#[cfg(feature = "async")]
+	wakers: Mutex<Vec<Waker>>,
+	/// The number of wakers in `wakers`, so that a count dropping to zero only
+	/// needs to lock them if there are any.
+	#[cfg(feature = "async")]
+	pending_wakers: AtomicUsize,
+	/// Wakes up [`Changes`] streams whenever the count changes.
+	#[cfg(feature = "async")]
+	changes: tokio::sync::Notify,
+	/// Incremented whenever the count changes while there are any [`Changes`]
+	/// streams, so that they can tell whether they've missed a change.
+	#[cfg(feature = "async")]
+	version: AtomicUsize,
+	/// The number of [`Changes`] streams currently alive.
+	#[cfg(feature = "async")]
+	watchers: AtomicUsize,
+	/// The maximum number of tickets that can be held at once, if any.
+	capacity: Option<usize>,
+	/// Whether threads waiting for capacity are served in FIFO order.
+	fair: bool,
+	/// The next turn to hand out to a thread waiting for capacity, in fair
+	/// mode.
+	next_turn: AtomicUsize,
+	/// The turn of the thread currently allowed to take capacity, in fair
+	/// mode. Once this catches up with `next_turn`, nobody is queued.
+	///
+	/// This is only changed while holding the lock on `skipped_turns`.
+	now_serving: AtomicUsize,
+	/// Turns given up by threads whose deadline passed before it was their
+	/// turn, in fair mode.
+	skipped_turns: Mutex<Vec<usize>>,
+	/// Callbacks to run whenever the count drops to zero.
+	drain_callbacks: Mutex<Vec<Callback>>,
+	/// The number of callbacks in `drain_callbacks`, so that a count dropping
+	/// to zero only needs to lock them if there are any.
+	drain_callback_count: AtomicUsize,
+	/// Callbacks to run whenever the count rises from zero.
+	activate_callbacks: Mutex<Vec<Callback>>,
+	/// The number of callbacks in `activate_callbacks`, so that a count rising
+	/// from zero only needs to lock them if there are any.
+	activate_callback_count: AtomicUsize,
+	/// Channels to send a message to whenever the count drops to zero.
+	#[cfg(feature = "std")]
+	subscribers: Mutex<Vec<std::sync::mpsc::Sender<()>>>,
+	/// The number of channels in `subscribers`, so that a count dropping to
+	/// zero only needs to lock them if there are any.
+	#[cfg(feature = "std")]
+	subscriber_count: AtomicUsize,
+	/// Called with the count if the counter is dropped while it's nonzero, if
+	/// configured with [`ThreadCounterBuilder::on_leak()`].
+	leak_callback: Option<CountCallback>,
+	/// Called when the count crosses a threshold, if configured with
+	/// [`ThreadCounterBuilder::on_high_water()`].
+	high_water: Option<HighWater>,
+	/// Whether to fail a debug assertion if the counter is dropped while it's
+	/// nonzero, if configured with
+	/// [`ThreadCounterBuilder::expect_zero_on_drop()`].
+	expect_zero_on_drop: bool,
+	/// A gauge mirroring the count, if configured with
+	/// [`ThreadCounterBuilder::metric()`].
+	#[cfg(feature = "metrics")]
+	gauge: Option<metrics::Gauge>,
+	/// Whether to record when the count last changed, if configured with
+	/// [`ThreadCounterBuilder::track_idle()`].
+	#[cfg(feature = "std")]
+	track_idle: bool,
+	/// When the count last changed, in nanoseconds since [`epoch()`], or
+	/// zero if it never has or isn't tracked.
+	#[cfg(feature = "std")]
+	last_change: AtomicU64,
+	/// The number of operations whose tickets were dropped while panicking.
+	#[cfg(feature = "std")]
+	panicked: AtomicUsize,
+	/// Callbacks to run whenever a ticket is dropped while panicking.
+	#[cfg(feature = "std")]
+	panic_callbacks: Mutex<Vec<Callback>>,
+	/// Called with how long a [`TimedTicket`] was held for, if it was held for
+	/// too long, if configured with [`ThreadCounterBuilder::on_slow_ticket()`].
+	#[cfg(feature = "std")]
+	slow_callback: Option<DurationCallback>,
 }
 
 impl RawThreadCounter {
+	/// Creates a new thread counter, starting at zero.
+	///
+	/// This is the same as [`RawThreadCounter::default()`], but can be used in
+	/// `const` contexts, such as initializing a `static` without any lazy
+	/// initialization.
+	///
+	/// ```rust
+	/// use thread_counter::RawThreadCounter;
+	///
+	/// static COUNTER: RawThreadCounter = RawThreadCounter::new();
+	///
+	/// COUNTER.increment();
+	/// assert_eq!(COUNTER.count(), 1);
+	/// COUNTER.decrement();
+	/// assert!(COUNTER.try_wait());
+	/// ```
+	#[cfg(not(loom))]
+	pub const fn new() -> Self {
+		empty_counter!()
+	}
+
+	/// Creates a new thread counter, starting at zero.
+	///
+	/// Loom's primitives can't be created in `const` contexts, so this isn't
+	/// `const` when model-checking with loom.
+	#[cfg(loom)]
+	pub fn new() -> Self {
+		empty_counter!()
+	}
+
+	/// Creates a new thread counter, starting at `count` rather than zero.
+	///
+	/// See [`ThreadCounter::with_count()`].
+	pub fn with_count(count: usize) -> Self {
+		let count = count.min(MAX_COUNT);
+		let counter = Self::new();
+		counter.count.store(count, Ordering::Relaxed);
+		counter.peak.store(count, Ordering::Relaxed);
+		counter.delta_base.store(count, Ordering::Relaxed);
+		counter
+			.generation
+			.store((count > 0) as u64, Ordering::Relaxed);
+		counter
+	}
+
+	/// Returns the current value of the thread counter.
+	///
+	/// This never takes a lock, nor waits on other threads to finish their
+	/// work.
+	///
+	/// # Note
+	/// The returned value is only a snapshot, and may already be stale by the
+	/// time it is returned, as other threads can increment or decrement the
+	/// counter at any moment.
+	pub fn count(&self) -> usize {
+		self.count.load(Ordering::SeqCst) & MAX_COUNT
+	}
+
+	/// Returns the current count, without synchronizing with other threads.
+	///
+	/// This is meant for sampling the count very frequently, such as from a
+	/// metrics thread feeding a dashboard. Neither this nor
+	/// [`RawThreadCounter::count()`] ever takes a lock, but this reads the
+	/// count with [`Ordering::Relaxed`], which is cheaper on some platforms.
+	///
+	/// # Note
+	/// The count may lag slightly behind changes made by other threads, and
+	/// reading it says nothing about what else those threads have done, so
+	/// don't use this to decide whether their work is finished.
+	pub fn count_approx(&self) -> usize {
+		self.count.load(Ordering::Relaxed) & MAX_COUNT
+	}
+
+	/// Returns the current count if it's nonzero, or `None` if it's zero.
+	///
+	/// This reads the count only once, so unlike checking
+	/// [`RawThreadCounter::is_empty()`] and then calling
+	/// [`RawThreadCounter::count()`], the answer can't change in between, and
+	/// code that needs a nonzero count doesn't have to check it again.
+	///
+	/// ```rust
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// assert_eq!(counter.count_nonzero(), None);
+	/// let _ticket = counter.ticket().unwrap();
+	/// assert_eq!(counter.count_nonzero().map(|count| count.get()), Some(1));
+	/// ```
+	pub fn count_nonzero(&self) -> Option<NonZeroUsize> {
+		NonZeroUsize::new(self.count())
+	}
+
+	/// Returns the counter's current generation, which is the number of times
+	/// the count has gone from zero to nonzero.
+	///
+	/// In a long-running service, the counter repeatedly rises and drains.
+	/// Comparing generations tells whether an empty counter is still in the
+	/// same idle period as before, or whether work has happened in between.
+	/// Counters created with a nonzero count start at generation `1`.
+	///
+	/// ```rust
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// assert_eq!(counter.generation(), 0);
+	/// let first = counter.ticket().unwrap();
+	/// let second = counter.ticket().unwrap();
+	/// assert_eq!(counter.generation(), 1);
+	///
+	/// drop((first, second));
+	/// let _third = counter.ticket().unwrap();
+	/// assert_eq!(counter.generation(), 2);
+	/// ```
+	pub fn generation(&self) -> u64 {
+		self.generation.load(Ordering::SeqCst)
+	}
+
+	/// Returns how long it's been since the count last changed, or `None` if
+	/// it never has.
+	///
+	/// Combined with [`RawThreadCounter::is_empty()`], this makes it easy to
+	/// implement idle shutdowns, such as shutting down once nothing has
+	/// happened for five minutes, without keeping a timestamp alongside the
+	/// counter.
+	///
+	/// # Note
+	/// Recording the time of every change costs more than the change itself,
+	/// so this is only tracked for counters built with
+	/// [`ThreadCounterBuilder::track_idle()`]. For any other counter, this
+	/// always returns `None`.
+	///
+	/// ```rust
+	/// use std::time::Duration;
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::builder().track_idle(true).build();
+	/// assert_eq!(counter.idle_for(), None);
+	/// // Decrements that leave the count at zero don't change it.
+	/// counter.decrement();
+	/// counter.reset();
+	/// assert_eq!(counter.idle_for(), None);
+	/// drop(counter.ticket().unwrap());
+	/// assert!(counter.idle_for() < Some(Duration::from_secs(1)));
+	/// ```
+	#[cfg(feature = "std")]
+	pub fn idle_for(&self) -> Option<Duration> {
+		match self.last_change.load(Ordering::Relaxed) {
+			0 => None,
+			nanos => Some(
+				epoch()
+					.elapsed()
+					.saturating_sub(Duration::from_nanos(nanos)),
+			),
+		}
+	}
+
+	/// Returns `true` if the count is currently zero.
+	///
+	/// This is the same check as [`RawThreadCounter::try_wait()`], but reads
+	/// better in conditionals. Just like [`RawThreadCounter::count()`], it
+	/// never blocks, and the answer may already be stale.
+	///
+	/// ```rust
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// assert!(counter.is_empty());
+	/// let _ticket = counter.ticket().unwrap();
+	/// assert!(!counter.is_empty());
+	/// ```
+	pub fn is_empty(&self) -> bool {
+		self.count() == 0
+	}
+
+	/// Returns `true` if the count is currently exactly `n`.
+	///
+	/// This is just `count() == n`, but can make conditionals and assertions
+	/// a little more readable.
+	pub fn has_count(&self, n: usize) -> bool {
+		self.count() == n
+	}
+
+	/// Returns the highest value the thread counter has reached, since it was
+	/// created or since the last call to [`RawThreadCounter::reset_peak()`].
+	///
+	/// This is useful for capacity planning, and is far cheaper and more
+	/// accurate than sampling [`RawThreadCounter::count()`] in a loop.
+	pub fn peak(&self) -> usize {
+		self.peak.load(Ordering::Relaxed)
+	}
+
+	/// Resets the peak tracked by [`RawThreadCounter::peak()`] to the current
+	/// count.
+	pub fn reset_peak(&self) {
+		self.peak.store(self.count(), Ordering::Relaxed);
+	}
+
+	/// Returns the net change in the count since the last call to this
+	/// method, or since the counter was created.
+	///
+	/// Every increment counts as `+1`, and every decrement as `-1`, so this is
+	/// handy for computing throughput in a metrics loop. A change too large
+	/// to fit in an [`isize`] saturates.
+	///
+	/// # Note
+	/// Increments and decrements that cancel each other out between two calls
+	/// aren't visible here. This is just the difference between the current
+	/// count and the one seen by the previous call, which keeps incrementing
+	/// and decrementing just as cheap as before. If multiple threads call this,
+	/// each change is still only reported once.
+	///
+	/// ```rust
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::with_count(2);
+	/// counter.increment_by(3);
+	/// counter.decrement();
+	/// assert_eq!(counter.take_delta(), 2);
+	///
+	/// counter.decrement_by(4);
+	/// assert_eq!(counter.take_delta(), -4);
+	/// assert_eq!(counter.take_delta(), 0);
+	/// ```
+	pub fn take_delta(&self) -> isize {
+		let count = self.count();
+		let base = self.delta_base.swap(count, Ordering::Relaxed);
+		match count >= base {
+			true => isize::try_from(count - base).unwrap_or(isize::MAX),
+			false => isize::try_from(base - count).map_or(isize::MIN, |delta| -delta),
+		}
+	}
+
+	/// Returns the maximum number of tickets that can be held at once, or
+	/// `None` if the counter is unbounded.
+	///
+	/// See [`ThreadCounter::with_capacity()`].
+	pub fn capacity(&self) -> Option<usize> {
+		self.capacity
+	}
+
+	/// Returns `true` if the counter has a capacity, and the count has reached
+	/// it, so that [`ThreadCounter::ticket()`] would block.
+	///
+	/// Counters without a capacity are never at capacity.
+	///
+	/// ```rust
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::with_capacity(1);
+	/// assert!(!counter.at_capacity());
+	/// let _ticket = counter.ticket().unwrap();
+	/// assert!(counter.at_capacity());
+	/// ```
+	pub fn at_capacity(&self) -> bool {
+		self.capacity
+			.is_some_and(|capacity| self.count() >= capacity)
+	}
+
 	/// Increments the thread counter.
 	///
+	/// This ignores the counter's capacity, if it has one.
+	///
 	/// # Note
 	/// It's preferable to use [`ThreadCounter::ticket()`] instead, which
 	/// ensures that the count is automatically decremented when the ticket is
 	/// dropped.
 	pub fn increment(&self) {
-		let mut count = self.count.lock();
-		*count += 1;
+		self.increment_by(1);
+	}
+
+	/// Increments the thread counter by `n` at once.
+	///
+	/// This is equivalent to calling [`RawThreadCounter::increment()`] `n`
+	/// times, but only updates the count once.
+	///
+	/// # Overflow
+	/// If the count would overflow, it saturates at `isize::MAX` instead of
+	/// wrapping around to a small value, which could make
+	/// [`RawThreadCounter::wait()`] return while operations are still running.
+	/// Once saturated, the count can no longer be trusted to return to zero,
+	/// but reaching this takes `isize::MAX` operations, which realistically
+	/// only happens with a runaway loop on a 32-bit target.
+	///
+	/// ```rust
+	/// use thread_counter::RawThreadCounter;
+	///
+	/// let counter = RawThreadCounter::with_count(isize::MAX as usize - 1);
+	/// counter.increment_by(2);
+	/// assert_eq!(counter.count(), isize::MAX as usize);
+	/// ```
+	pub fn increment_by(&self, n: usize) {
+		self.increment_by_get(n);
+	}
+
+	/// Increments the thread counter, returning the new count.
+	///
+	/// This is handy for things like logging "operation #N started", as
+	/// calling [`RawThreadCounter::count()`] afterwards could already see
+	/// other threads' changes.
+	///
+	/// ```rust
+	/// use thread_counter::RawThreadCounter;
+	///
+	/// let counter = RawThreadCounter::default();
+	/// assert_eq!(counter.increment_get(), 1);
+	/// assert_eq!(counter.increment_get(), 2);
+	/// assert_eq!(counter.decrement_get(), 1);
+	/// ```
+	pub fn increment_get(&self) -> usize {
+		self.increment_by_get(1)
+	}
+
+	/// Increments the thread counter by `n` at once, returning the new count.
+	///
+	/// See [`RawThreadCounter::increment_by()`] and
+	/// [`RawThreadCounter::increment_get()`].
+	pub fn increment_by_get(&self, n: usize) -> usize {
+		let (previous, count) = self.update(|count| count.saturating_add(n));
+		self.increased(previous, count);
+		count
+	}
+
+	/// Increments the thread counter through exclusive access.
+	///
+	/// This is the same as [`RawThreadCounter::increment()`], but as nothing
+	/// else can be touching the count at the same time, it's updated directly
+	/// rather than atomically. This speeds up setup and teardown phases where
+	/// the counter isn't shared yet, or anymore. Callbacks, the peak and the
+	/// generation are all still updated as usual.
+	///
+	/// [`ThreadCounter`] is always shared, so this is only available on a
+	/// [`RawThreadCounter`] that's owned or mutably borrowed.
+	///
+	/// ```rust
+	/// use thread_counter::RawThreadCounter;
+	///
+	/// let mut counter = RawThreadCounter::new();
+	/// for _ in 0..3 {
+	/// 	counter.increment_mut();
+	/// }
+	/// counter.decrement_mut();
+	/// assert_eq!(counter.count(), 2);
+	/// assert_eq!(counter.peak(), 3);
+	/// ```
+	pub fn increment_mut(&mut self) {
+		let previous = atomic::update_mut(&mut self.count, |word| {
+			with_count_bits(word, |count| count.saturating_add(1))
+		}) & MAX_COUNT;
+		self.increased(previous, previous.saturating_add(1).min(MAX_COUNT));
+	}
+
+	/// Increments the thread counter if there's room below its capacity,
+	/// returning whether it did so.
+	///
+	/// This is the manual counterpart of [`ThreadCounter::try_ticket()`], for
+	/// when the matching decrement happens somewhere a [`Ticket`]'s `Drop`
+	/// can't easily reach, such as across an FFI or callback boundary. Just
+	/// like [`ThreadCounter::try_ticket()`], it never blocks, and fails if
+	/// the counter is [closed](RawThreadCounter::close()), or if there's a
+	/// queue of threads waiting for capacity in fair mode. Counters without
+	/// a capacity always have room.
+	///
+	/// # Note
+	/// Every successful call needs to be balanced by exactly one call to
+	/// [`RawThreadCounter::decrement()`], or the count will never get back
+	/// to zero, and the capacity will be used up for good.
+	///
+	/// ```rust
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::with_capacity(1);
+	/// assert!(counter.try_increment());
+	/// assert!(!counter.try_increment());
+	/// counter.decrement();
+	/// assert!(counter.try_increment());
+	/// ```
+	pub fn try_increment(&self) -> bool {
+		self.try_acquire(1)
 	}
 
 	/// Decrements the thread counter.
 	///
-	/// If the count reaches zero, it notifies all waiting threads.
+	/// If the count is already zero, it stays at zero, rather than
+	/// underflowing.
+	///
+	/// Every decrement notifies all waiting threads, so that threads waiting
+	/// for the count to drop to a threshold (see
+	/// [`RawThreadCounter::wait_for()`]) can re-check their condition. If no
+	/// threads are waiting, the condition variable isn't touched at all.
 	///
 	/// # Note
 	/// It's preferable to use [`ThreadCounter::ticket()`] instead, which
 	/// ensures that the count is automatically decremented when the ticket is
 	/// dropped.
 	pub fn decrement(&self) {
-		let mut count = self.count.lock();
-		*count -= 1;
-		if *count == 0 {
-			self.condvar.notify_all();
-		}
+		self.decrement_by(1);
 	}
 
-	/// Waits for the counter to reach zero, with an optional timeout.
+	/// Decrements the thread counter by `n` at once.
 	///
-	/// # Arguments
-	/// * `timeout` - An optional duration to wait. If `None`, waits
-	///   indefinitely.
+	/// This is equivalent to calling [`RawThreadCounter::decrement()`] `n`
+	/// times, but only updates the count once, and only notifies waiting
+	/// threads once, after the whole amount has been subtracted.
+	///
+	/// # Underflow
+	/// If `n` is larger than the current count, the count saturates at zero
+	/// instead of underflowing, so unbalanced decrements can never make
+	/// [`RawThreadCounter::wait()`] block forever.
+	pub fn decrement_by(&self, n: usize) {
+		self.decrement_by_get(n);
+	}
+
+	/// Decrements the thread counter, returning the new count.
+	///
+	/// See [`RawThreadCounter::decrement()`] and
+	/// [`RawThreadCounter::increment_get()`].
+	pub fn decrement_get(&self) -> usize {
+		self.decrement_by_get(1)
+	}
+
+	/// Decrements the thread counter by `n` at once, returning the new count.
+	///
+	/// See [`RawThreadCounter::decrement_by()`] and
+	/// [`RawThreadCounter::increment_get()`].
+	pub fn decrement_by_get(&self, n: usize) -> usize {
+		let (previous, count) = self.update(|count| count.saturating_sub(n));
+		self.changed(previous, count);
+		count
+	}
+
+	/// Decrements the thread counter through exclusive access.
+	///
+	/// See [`RawThreadCounter::increment_mut()`]. Just like
+	/// [`RawThreadCounter::decrement()`], this saturates at zero.
+	pub fn decrement_mut(&mut self) {
+		let previous = atomic::update_mut(&mut self.count, |word| {
+			with_count_bits(word, |count| count.saturating_sub(1))
+		}) & MAX_COUNT;
+		self.changed(previous, previous.saturating_sub(1));
+	}
+
+	/// Decrements the thread counter, returning whether this decrement is the
+	/// one that made the count reach zero.
+	///
+	/// This lets the last thread out do any cleanup directly, without needing
+	/// a separate [`RawThreadCounter::wait()`]. Exactly one call returns
+	/// `true` for each time the counter is drained; decrementing a counter
+	/// that's already at zero returns `false`.
+	///
+	/// ```rust
+	/// use thread_counter::RawThreadCounter;
+	///
+	/// let counter = RawThreadCounter::default();
+	/// counter.increment_by(2);
+	/// assert!(!counter.decrement_checked());
+	/// assert!(counter.decrement_checked());
+	/// assert!(!counter.decrement_checked());
+	/// ```
+	pub fn decrement_checked(&self) -> bool {
+		self.decrement_by_checked(1)
+	}
+
+	/// Decrements the thread counter by `n` at once, returning whether this
+	/// made the count reach zero.
+	///
+	/// See [`RawThreadCounter::decrement_by()`] and
+	/// [`RawThreadCounter::decrement_checked()`].
+	pub fn decrement_by_checked(&self, n: usize) -> bool {
+		let (previous, count) = self.update(|count| count.saturating_sub(n));
+		self.changed(previous, count)
+	}
+
+	/// Atomically reads and modifies the count, for decisions that would
+	/// otherwise race, like "if the count is below 10, increment it and do
+	/// something, else bail out".
+	///
+	/// `f` is given a mutable reference to a copy of the count, and whatever it
+	/// leaves there becomes the new count, which is then committed with a
+	/// single compare-and-swap. Waiters, drain callbacks and the like are
+	/// notified of the change just as if it had been made with
+	/// [`RawThreadCounter::increment_by()`] or
+	/// [`RawThreadCounter::decrement_by()`]. The counter's capacity, if it has
+	/// one, isn't enforced.
+	///
+	/// # Note
+	/// The count isn't locked while `f` runs. If another thread changes it in
+	/// the meantime, the compare-and-swap fails, and `f` is called again with
+	/// the fresh count. As such, `f` may run several times, and should only
+	/// decide what to do, leaving any side effects until after this returns.
 	///
 	/// # Returns
-	/// * `true` if the count reached zero.
-	/// * `false` if the timeout was reached before the count reached zero.
-	pub fn wait(&self, timeout: impl Into<Option<Duration>>) -> bool {
-		let mut count = self.count.lock();
-		let condition = |count: &mut usize| *count > 0;
-		match timeout.into() {
-			Some(timeout) => !self
-				.condvar
-				.wait_while_for(&mut count, condition, timeout)
-				.timed_out(),
-			None => {
-				self.condvar.wait_while(&mut count, condition);
-				true
+	/// Whatever the final, successful call of `f` returned.
+	///
+	/// ```rust
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::with_count(9);
+	/// let admit = |count: &mut usize| {
+	/// 	let admitted = *count < 10;
+	/// 	if admitted {
+	/// 		*count += 1;
+	/// 	}
+	/// 	admitted
+	/// };
+	/// assert!(counter.update_count(admit));
+	/// assert!(!counter.update_count(admit));
+	/// assert_eq!(counter.count(), 10);
+	/// ```
+	pub fn update_count<R>(&self, mut f: impl FnMut(&mut usize) -> R) -> R {
+		let mut word = self.count.load(Ordering::SeqCst);
+		loop {
+			let previous = word & MAX_COUNT;
+			let mut count = previous;
+			let result = f(&mut count);
+			let count = count.min(MAX_COUNT);
+			match self.count.compare_exchange_weak(
+				word,
+				(word & CLOSED) | count,
+				Ordering::SeqCst,
+				Ordering::SeqCst,
+			) {
+				Ok(_) => {
+					if count > previous {
+						self.increased(previous, count);
+					} else if count < previous {
+						self.changed(previous, count);
+					}
+					return result;
+				}
+				Err(actual) => word = actual,
 			}
 		}
 	}
-}
 
-impl Default for RawThreadCounter {
-	fn default() -> Self {
-		Self {
-			count: Mutex::new(0),
-			condvar: Condvar::new(),
-		}
+	/// Forces the thread counter back to zero, waking up all waiting threads.
+	///
+	/// This is mostly useful in tests, or to recover after a section of code
+	/// that may have leaked increments.
+	///
+	/// # Warning
+	/// Any [`Ticket`]s that are still alive will decrement the counter when
+	/// they're dropped, which will throw off the count of any tickets taken
+	/// after the reset. As such, this is best used when no tickets are live.
+	///
+	/// ```rust
+	/// use std::thread;
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// counter.increment_by(3);
+	///
+	/// let waiter = thread::spawn({
+	/// 	let counter = counter.clone();
+	/// 	move || counter.wait_indefinitely()
+	/// });
+	/// // Make sure the waiter is actually waiting before resetting.
+	/// while counter.waiters() == 0 {
+	/// 	thread::yield_now();
+	/// }
+	/// counter.reset();
+	/// assert!(waiter.join().unwrap().is_completed());
+	/// assert_eq!(counter.count(), 0);
+	/// ```
+	pub fn reset(&self) {
+		self.decrement_all();
 	}
-}
-
-/// A RAII guard for automatically managing the thread count.
-///
-/// When a `Ticket` is created, it increments the associated thread counter.
-/// When the `Ticket` is dropped, it automatically decrements the counter.
-pub struct Ticket {
-	counter: ThreadCounter,
-}
 
-impl Drop for Ticket {
-	fn drop(&mut self) {
-		self.counter.decrement();
+	/// Forces the thread counter back to zero, waking up all waiting threads,
+	/// and returns the count from just before it was cleared.
+	///
+	/// This is the same as [`RawThreadCounter::reset()`], but lets you find
+	/// out how many operations were still outstanding, for example to log how
+	/// many were forcibly cancelled.
+	///
+	/// # Warning
+	/// Just like with [`RawThreadCounter::reset()`], any [`Ticket`]s that are
+	/// still alive will decrement the counter when they're dropped. The count
+	/// saturates at zero rather than underflowing, but those late decrements
+	/// will still eat into the count of any tickets taken after this call.
+	///
+	/// ```rust
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// counter.increment_by(3);
+	/// assert_eq!(counter.decrement_all(), 3);
+	/// assert_eq!(counter.count(), 0);
+	/// assert_eq!(counter.decrement_all(), 0);
+	/// ```
+	pub fn decrement_all(&self) -> usize {
+		// Leave the closed bit alone.
+		let previous = self.count.fetch_and(CLOSED, Ordering::SeqCst) & MAX_COUNT;
+		self.changed(previous, 0);
+		previous
 	}
+
+	/// Lowers the count to `max` if it's currently above it, returning how
+	/// much was taken off.
+	///
+	/// This is a controlled correction, for when reconciling the count
+	/// against some other source of truth shows that it has drifted upwards,
+	/// without throwing away the part of it that's still accurate like
+	/// [`RawThreadCounter::reset()`] would. Waiting threads are only notified
+	/// if the count actually dropped.
+	///
+	/// # Warning
+	/// Just like with [`RawThreadCounter::reset()`], any [`Ticket`]s that are
+	/// still alive will decrement the counter when they're dropped, even if
+	/// their part of the count was what got removed. The count saturates at
+	/// zero rather than underflowing, but it will then undercount any tickets
+	/// taken after this call.
+	///
+	/// ```rust
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// counter.increment_by(5);
+	/// assert_eq!(counter.clamp_to(3), 2);
+	/// assert_eq!(counter.count(), 3);
+	/// assert_eq!(counter.clamp_to(4), 0);
+	/// assert_eq!(counter.count(), 3);
+	/// ```
+	pub fn clamp_to(&self, max: usize) -> usize {
+		let (previous, _) = self.update(|count| count.min(max));
+		if previous <= max {
+			return 0;
+		}
+		self.changed(previous, max);
+		previous - max
+	}
+
+	/// Moves all of `other`'s count over to this counter, returning how much
+	/// was moved.
+	///
+	/// This is for retiring one counter into another, such as when a
+	/// sub-pool is shut down and its outstanding work should count towards
+	/// its parent. `other` is zeroed first, then this counter is incremented,
+	/// and waiting threads are notified on both, just like with
+	/// [`RawThreadCounter::decrement_all()`] and
+	/// [`RawThreadCounter::increment_by()`].
+	///
+	/// # Note
+	/// The count is never locked, so there's no lock ordering to get wrong,
+	/// and two counters merging into each other at the same time can't
+	/// deadlock. However, the move isn't atomic across both counters: for a
+	/// moment, the moved count is on neither of them.
+	///
+	/// # Warning
+	/// This only moves the count, not any [`Ticket`]s. Tickets taken from
+	/// `other` still decrement `other` when they're dropped, not this
+	/// counter, so this is best used with manual increments, rather than
+	/// while tickets from `other` are still alive.
+	///
+	/// ```rust
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let parent = ThreadCounter::with_count(1);
+	/// let pool = ThreadCounter::with_count(2);
+	/// assert_eq!(parent.merge_from(&pool), 2);
+	/// assert_eq!(parent.count(), 3);
+	/// assert!(pool.is_empty());
+	/// ```
+	pub fn merge_from(&self, other: &RawThreadCounter) -> usize {
+		let moved = other.decrement_all();
+		if moved > 0 {
+			self.increment_by(moved);
+		}
+		moved
+	}
+
+	/// Waits for the counter to reach zero, and then resets its statistics,
+	/// so it can be reused as if it were freshly created.
+	///
+	/// This is mostly useful for tearing down between tests, without needing
+	/// to reconstruct the counter. The count is left alone, as it's already
+	/// zero, but the [peak](RawThreadCounter::peak()),
+	/// [generation](RawThreadCounter::generation()) and
+	/// [panic count](RawThreadCounter::panic_count()) are all reset to zero.
+	/// Registered callbacks, the capacity, and whether the counter is closed
+	/// are left as they are.
+	///
+	/// # Arguments
+	/// * `timeout` - An optional duration to wait. If `None`, waits
+	///   indefinitely.
+	///
+	/// # Returns
+	/// The result of waiting, just like [`RawThreadCounter::wait()`]. Nothing
+	/// is reset unless it's [`WaitResult::Completed`].
+	///
+	/// # Note
+	/// The reset happens right after waiting, not atomically with it, so any
+	/// ticket taken in between is invisible to the fresh statistics. As the
+	/// generation goes backwards, threads waiting in
+	/// [`RawThreadCounter::wait_for_generation()`] will also wait for that
+	/// many more cycles of work than they otherwise would have.
+	///
+	/// ```rust
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// drop(counter.ticket_n(3).unwrap());
+	/// assert_eq!(counter.peak(), 3);
+	/// assert_eq!(counter.generation(), 1);
+	///
+	/// assert!(counter.flush(None).is_completed());
+	/// assert_eq!(counter.peak(), 0);
+	/// assert_eq!(counter.generation(), 0);
+	/// ```
+	#[cfg(feature = "std")]
+	pub fn flush(&self, timeout: impl Into<Option<Duration>>) -> WaitResult {
+		let result = self.wait(timeout);
+		if result.is_completed() {
+			self.peak.store(0, Ordering::Relaxed);
+			self.generation.store(0, Ordering::SeqCst);
+			self.panicked.store(0, Ordering::SeqCst);
+		}
+		result
+	}
+
+	/// Registers a callback to be run whenever the count drops to zero.
+	///
+	/// This is an alternative to dedicating a thread to
+	/// [`RawThreadCounter::wait()`]. Any number of callbacks can be registered,
+	/// and they're run in the order they were registered.
+	///
+	/// # Note
+	/// Callbacks are run on the thread that performed the final decrement
+	/// (usually by dropping the last [`Ticket`]), and are free to use the
+	/// counter themselves. As the count isn't locked while they run, other
+	/// threads may have already incremented the counter again by the time a
+	/// callback runs.
+	///
+	/// ```rust
+	/// use std::sync::{
+	/// 	atomic::{AtomicUsize, Ordering},
+	/// 	Arc,
+	/// };
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let drains = Arc::new(AtomicUsize::new(0));
+	/// let counter = ThreadCounter::default();
+	/// counter.on_drain({
+	/// 	let drains = drains.clone();
+	/// 	move || {
+	/// 		drains.fetch_add(1, Ordering::Relaxed);
+	/// 	}
+	/// });
+	///
+	/// let ticket = counter.ticket().unwrap();
+	/// drop(ticket);
+	/// assert_eq!(drains.load(Ordering::Relaxed), 1);
+	/// ```
+	pub fn on_drain(&self, f: impl Fn() + Send + Sync + 'static) {
+		let mut callbacks = self.drain_callbacks.lock();
+		callbacks.push(Arc::new(f));
+		self.drain_callback_count
+			.store(callbacks.len(), Ordering::SeqCst);
+	}
+
+	/// Registers a callback to be run whenever the count rises from zero.
+	///
+	/// Together with [`RawThreadCounter::on_drain()`], this turns the counter
+	/// into a gate for a lazily managed resource, such as a background task
+	/// that should only run while there's work. Any number of callbacks can
+	/// be registered, and they're run in the order they were registered.
+	///
+	/// # Note
+	/// Callbacks are run on the thread that performed the first increment,
+	/// before it returns. As the count isn't locked while they run, if the
+	/// counter drains again straight away on another thread, drain callbacks
+	/// may run before, or at the same time as, activation callbacks for the
+	/// same period of activity.
+	///
+	/// ```rust
+	/// use std::sync::{
+	/// 	atomic::{AtomicBool, Ordering},
+	/// 	Arc,
+	/// };
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let running = Arc::new(AtomicBool::new(false));
+	/// let counter = ThreadCounter::default();
+	/// counter.on_activate({
+	/// 	let running = running.clone();
+	/// 	move || running.store(true, Ordering::Relaxed)
+	/// });
+	/// counter.on_drain({
+	/// 	let running = running.clone();
+	/// 	move || running.store(false, Ordering::Relaxed)
+	/// });
+	///
+	/// let ticket = counter.ticket().unwrap();
+	/// assert!(running.load(Ordering::Relaxed));
+	/// drop(ticket);
+	/// assert!(!running.load(Ordering::Relaxed));
+	/// ```
+	pub fn on_activate(&self, f: impl Fn() + Send + Sync + 'static) {
+		let mut callbacks = self.activate_callbacks.lock();
+		callbacks.push(Arc::new(f));
+		self.activate_callback_count
+			.store(callbacks.len(), Ordering::SeqCst);
+	}
+
+	/// Returns a channel that receives a message whenever the count drops to
+	/// zero.
+	///
+	/// This is like [`RawThreadCounter::on_drain()`], but gives each
+	/// consumer its own independent receiver, which can be handed off to
+	/// another thread and polled or blocked on at its own pace. Messages
+	/// queue up until they're received, so no drain is missed.
+	///
+	/// # Note
+	/// A dropped receiver is cleaned up the next time the count drops to
+	/// zero, so subscribing and dropping receivers in a loop doesn't grow
+	/// without bound.
+	///
+	/// ```rust
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// let drains = counter.subscribe();
+	///
+	/// drop(counter.ticket().unwrap());
+	/// drop(counter.ticket().unwrap());
+	/// assert_eq!(drains.try_iter().count(), 2);
+	/// ```
+	#[cfg(feature = "std")]
+	pub fn subscribe(&self) -> std::sync::mpsc::Receiver<()> {
+		let (sender, receiver) = std::sync::mpsc::channel();
+		let mut subscribers = self.subscribers.lock();
+		subscribers.push(sender);
+		self.subscriber_count
+			.store(subscribers.len(), Ordering::SeqCst);
+		receiver
+	}
+
+	/// Returns the number of operations whose [`Ticket`]s were dropped while
+	/// their thread was panicking.
+	///
+	/// This gives some visibility into how many in-flight operations died
+	/// unexpectedly, rather than completing normally. A ticket from
+	/// [`ThreadCounter::ticket_n()`] counts for all of its operations.
+	///
+	/// ```rust
+	/// use std::thread;
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// let ticket = counter.ticket().unwrap();
+	/// let result = thread::spawn(move || {
+	/// 	let _ticket = ticket;
+	/// 	panic!("something went wrong");
+	/// })
+	/// .join();
+	///
+	/// assert!(result.is_err());
+	/// assert_eq!(counter.count(), 0);
+	/// assert_eq!(counter.panic_count(), 1);
+	/// ```
+	#[cfg(feature = "std")]
+	pub fn panic_count(&self) -> usize {
+		self.panicked.load(Ordering::SeqCst)
+	}
+
+	/// Registers a callback to be run whenever a [`Ticket`] is dropped while
+	/// its thread is panicking.
+	///
+	/// Just like with [`RawThreadCounter::on_drain()`], any number of
+	/// callbacks can be registered, and they're run in the order they were
+	/// registered.
+	///
+	/// # Note
+	/// Callbacks are run on the panicking thread, right before the ticket
+	/// decrements the counter. Panicking inside of a callback will abort the
+	/// process, as it'd be a panic during a panic.
+	#[cfg(feature = "std")]
+	pub fn on_panic(&self, f: impl Fn() + Send + Sync + 'static) {
+		self.panic_callbacks.lock().push(Arc::new(f));
+	}
+
+	/// Wakes up every thread currently waiting on this counter, even if the
+	/// count hasn't changed.
+	///
+	/// This is meant for things like shutdown signals, where threads blocked
+	/// in [`RawThreadCounter::wait()`] or similar should return early, so they
+	/// can observe some separate "shutting down" flag and bail out. Waits that
+	/// are cut short this way return [`WaitResult::Interrupted`], unless their
+	/// condition happened to be met at the same time, so callers should
+	/// re-check their own shutdown condition afterwards.
+	///
+	/// Only threads that are already waiting are affected; waits started
+	/// afterwards block as usual. Threads blocked in
+	/// [`ThreadCounter::ticket()`] waiting for capacity, and asynchronous
+	/// waiters, are not interrupted.
+	///
+	/// ```rust
+	/// use std::thread;
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// let _ticket = counter.ticket().unwrap();
+	///
+	/// let waiter = thread::spawn({
+	/// 	let counter = counter.clone();
+	/// 	move || counter.wait_indefinitely()
+	/// });
+	/// while counter.waiters() == 0 {
+	/// 	thread::yield_now();
+	/// }
+	/// counter.notify_all();
+	/// assert!(waiter.join().unwrap().is_interrupted());
+	/// ```
+	pub fn notify_all(&self) {
+		self.interrupts.fetch_add(1, Ordering::SeqCst);
+		self.wake_waiters();
+	}
+
+	/// Closes the counter, so that [`ThreadCounter::ticket()`] and friends
+	/// stop handing out new tickets.
+	///
+	/// Tickets that are already held are unaffected, and still decrement the
+	/// counter when dropped. Threads blocked in [`ThreadCounter::ticket()`]
+	/// waiting for capacity are woken up, and fail. Incrementing the counter
+	/// directly with [`RawThreadCounter::increment()`] still works, as there's
+	/// no ticket to refuse.
+	///
+	/// There's no way to reopen a counter once it's been closed. Refused
+	/// tickets never touch the count, so they don't run any callbacks or
+	/// start a new generation either.
+	///
+	/// See [`RawThreadCounter::drain()`] to also wait for the remaining
+	/// tickets to be dropped.
+	///
+	/// ```rust
+	/// use std::sync::{
+	/// 	atomic::{AtomicUsize, Ordering},
+	/// 	Arc,
+	/// };
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let drains = Arc::new(AtomicUsize::new(0));
+	/// let counter = ThreadCounter::builder()
+	/// 	.on_drain({
+	/// 		let drains = drains.clone();
+	/// 		move || {
+	/// 			drains.fetch_add(1, Ordering::Relaxed);
+	/// 		}
+	/// 	})
+	/// 	.build();
+	///
+	/// counter.close();
+	/// assert!(counter.ticket().is_err());
+	/// assert!(counter.try_ticket().is_none());
+	/// assert_eq!(drains.load(Ordering::Relaxed), 0);
+	/// assert_eq!(counter.generation(), 0);
+	/// assert_eq!(counter.peak(), 0);
+	/// ```
+	pub fn close(&self) {
+		if self.count.fetch_or(CLOSED, Ordering::SeqCst) & CLOSED == 0 {
+			atomic::store_load_fence();
+			if self.waiters() > 0 {
+				self.wake_waiters();
+			}
+		}
+	}
+
+	/// Returns `true` if the counter has been closed with
+	/// [`RawThreadCounter::close()`].
+	pub fn is_closed(&self) -> bool {
+		self.count.load(Ordering::SeqCst) & CLOSED != 0
+	}
+
+	/// Closes the counter, then waits for the count to reach zero, with an
+	/// optional timeout.
+	///
+	/// This is a one-shot graceful shutdown: once it returns
+	/// [`WaitResult::Completed`], every ticket has been dropped, and no more
+	/// will be handed out.
+	///
+	/// # Arguments
+	/// * `timeout` - An optional duration to wait for the counter to reach
+	///   zero. If `None`, it will wait indefinitely.
+	///
+	/// # Returns
+	/// Just like [`RawThreadCounter::wait()`].
+	///
+	/// ```rust
+	/// use std::thread;
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// let ticket = counter.ticket().unwrap();
+	/// thread::spawn(move || {
+	/// 	// Finish up some work, holding onto the ticket.
+	/// 	drop(ticket);
+	/// });
+	///
+	/// assert!(counter.drain(None).is_completed());
+	/// assert!(counter.ticket().is_err());
+	/// ```
+	#[cfg(feature = "std")]
+	pub fn drain(&self, timeout: impl Into<Option<Duration>>) -> WaitResult {
+		self.close();
+		self.wait(timeout)
+	}
+
+	/// Waits for the counter to reach zero, with an optional timeout.
+	///
+	/// This is equivalent to calling [`RawThreadCounter::wait_for()`] with a
+	/// target of `0`.
+	///
+	/// # Arguments
+	/// * `timeout` - An optional duration to wait. If `None`, waits
+	///   indefinitely. A timeout of [`Duration::ZERO`] checks the count once
+	///   without blocking, just like [`RawThreadCounter::try_wait()`], which
+	///   makes it a portable non-blocking poll. This goes for every other timed
+	///   wait, too.
+	///
+	/// # Returns
+	/// * [`WaitResult::Completed`] if the count reached zero.
+	/// * [`WaitResult::TimedOut`] if the timeout was reached before the count
+	///   reached zero.
+	/// * [`WaitResult::Interrupted`] if waiting was cut short by
+	///   [`RawThreadCounter::notify_all()`].
+	///
+	/// ```rust
+	/// use std::time::Duration;
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// assert!(counter.wait(Duration::ZERO).is_completed());
+	/// let _ticket = counter.ticket().unwrap();
+	/// assert!(counter.wait(Duration::ZERO).is_timed_out());
+	/// ```
+	#[cfg(feature = "std")]
+	pub fn wait(&self, timeout: impl Into<Option<Duration>>) -> WaitResult {
+		self.wait_for(0, timeout)
+	}
+
+	/// Waits for the counter to reach zero, without any timeout.
+	///
+	/// This is equivalent to `wait(None)`, but is also available without the
+	/// `std` feature, in which case it busy-waits.
+	///
+	/// # Returns
+	/// * [`WaitResult::Completed`] if the count reached zero.
+	/// * [`WaitResult::Interrupted`] if waiting was cut short by
+	///   [`RawThreadCounter::notify_all()`].
+	pub fn wait_indefinitely(&self) -> WaitResult {
+		self.block_while(|count| count > 0, None).0
+	}
+
+	/// Waits for the counter to reach zero, giving up once `deadline` has
+	/// passed.
+	///
+	/// This is useful when coordinating several waits against one shared
+	/// deadline, as there's no need to convert it into a [`Duration`] first.
+	///
+	/// # Arguments
+	/// * `deadline` - The point in time at which to stop waiting.
+	///
+	/// # Returns
+	/// * [`WaitResult::Completed`] if the count reached zero.
+	/// * [`WaitResult::TimedOut`] if the deadline passed before the count
+	///   reached zero.
+	/// * [`WaitResult::Interrupted`] if waiting was cut short by
+	///   [`RawThreadCounter::notify_all()`].
+	#[cfg(feature = "std")]
+	pub fn wait_deadline(&self, deadline: Instant) -> WaitResult {
+		self.block_while(|count| count > 0, Some(deadline)).0
+	}
+
+	/// Waits for the counter to reach zero, with an optional deadline.
+	///
+	/// This is [`RawThreadCounter::wait_deadline()`], but also accepting
+	/// `None` to wait indefinitely, so that a single call can forward an
+	/// optional deadline as-is. A deadline that has already passed is also
+	/// fine, and just checks the count once, so there's no need to turn it
+	/// into a (possibly zero) [`Duration`] first.
+	///
+	/// # Arguments
+	/// * `deadline` - An optional point in time at which to stop waiting. If
+	///   `None`, waits indefinitely.
+	///
+	/// # Returns
+	/// * [`WaitResult::Completed`] if the count reached zero.
+	/// * [`WaitResult::TimedOut`] if the deadline passed before the count
+	///   reached zero.
+	/// * [`WaitResult::Interrupted`] if waiting was cut short by
+	///   [`RawThreadCounter::notify_all()`].
+	///
+	/// ```rust
+	/// use std::time::{Duration, Instant};
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::with_count(1);
+	/// let deadline = Instant::now() + Duration::from_millis(10);
+	/// assert!(counter.wait_until(deadline).is_timed_out());
+	/// // Waiting again past the deadline doesn't block at all.
+	/// assert!(counter.wait_until(deadline).is_timed_out());
+	///
+	/// counter.decrement();
+	/// assert!(counter.wait_until(None).is_completed());
+	/// ```
+	#[cfg(feature = "std")]
+	pub fn wait_until(&self, deadline: impl Into<Option<Instant>>) -> WaitResult {
+		self.block_while(|count| count > 0, deadline.into()).0
+	}
+
+	/// Waits for the counter to reach zero, with an optional timeout, and
+	/// returns the count observed at the moment waiting ended.
+	///
+	/// This is useful for logging how many operations were still outstanding
+	/// when a wait timed out, e.g. to debug stuck shutdowns.
+	///
+	/// # Arguments
+	/// * `timeout` - An optional duration to wait. If `None`, waits
+	///   indefinitely.
+	///
+	/// # Returns
+	/// The number of operations still running when waiting ended, which is `0`
+	/// if the count reached zero, and nonzero if the timeout was reached first.
+	#[cfg(feature = "std")]
+	pub fn wait_remaining(&self, timeout: impl Into<Option<Duration>>) -> usize {
+		self.block_while(|count| count > 0, deadline_after(timeout.into()))
+			.1
+	}
+
+	/// Waits for the counter to reach zero, with an optional timeout, and
+	/// measures how long the wait took.
+	///
+	/// This is useful for diagnosing slow shutdowns, and saves wrapping every
+	/// call in [`Instant::now()`] bookkeeping. The measurement covers the whole
+	/// wait, including any spurious wakeups along the way.
+	///
+	/// # Arguments
+	/// * `timeout` - An optional duration to wait. If `None`, waits
+	///   indefinitely.
+	///
+	/// # Returns
+	/// The same [`WaitResult`] as [`RawThreadCounter::wait()`], along with the
+	/// time spent waiting.
+	#[cfg(feature = "std")]
+	pub fn wait_timed(&self, timeout: impl Into<Option<Duration>>) -> (WaitResult, Duration) {
+		let start = Instant::now();
+		let result = self.wait(timeout);
+		(result, start.elapsed())
+	}
+
+	/// Asynchronously waits for the counter to reach zero.
+	///
+	/// This is the asynchronous equivalent of [`RawThreadCounter::wait()`],
+	/// which yields to the executor instead of blocking the current thread. It
+	/// doesn't depend on any particular runtime.
+	///
+	/// ```rust
+	/// # #[tokio::main(flavor = "current_thread")]
+	/// # async fn main() {
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// let ticket = counter.ticket().unwrap();
+	/// tokio::spawn(async move {
+	/// 	// Do some work, then let go of the ticket.
+	/// 	drop(ticket);
+	/// });
+	/// counter.wait_async().await;
+	/// # }
+	/// ```
+	#[cfg(feature = "async")]
+	pub async fn wait_async(&self) {
+		loop {
+			// Register interest before checking the count, so a decrement
+			// happening in between can't be missed.
+			let mut drained = std::pin::pin!(self.drained.notified());
+			drained.as_mut().enable();
+			if self.count() == 0 {
+				return;
+			}
+			drained.await;
+		}
+	}
+
+	/// Polls whether the counter has reached zero, registering the current
+	/// task to be woken up when it does if it hasn't.
+	///
+	/// This is the low-level building block for integrating the counter into
+	/// hand-written [`Future`](core::future::Future)s, where
+	/// [`RawThreadCounter::wait_async()`] can't be awaited. Just like any other
+	/// poll function, it needs to be called again after the task is woken up,
+	/// as the count may have risen again in the meantime.
+	///
+	/// # Note
+	/// Each task polling for a drain is remembered until the next time the
+	/// count reaches zero, even if it stops polling before then.
+	///
+	/// ```rust
+	/// # #[tokio::main(flavor = "current_thread")]
+	/// # async fn main() {
+	/// use std::future::poll_fn;
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// let ticket = counter.ticket().unwrap();
+	/// tokio::spawn(async move {
+	/// 	drop(ticket);
+	/// });
+	/// poll_fn(|cx| counter.poll_wait(cx)).await;
+	/// assert_eq!(counter.count(), 0);
+	/// # }
+	/// ```
+	#[cfg(feature = "async")]
+	pub fn poll_wait(&self, cx: &mut Context<'_>) -> Poll<()> {
+		if self.count() == 0 {
+			return Poll::Ready(());
+		}
+		{
+			let mut wakers = self.wakers.lock();
+			if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+				wakers.push(cx.waker().clone());
+				self.pending_wakers.store(wakers.len(), Ordering::SeqCst);
+			}
+		}
+		// Check again now that the waker is registered, so a drain happening
+		// in between can't be missed.
+		match self.count() {
+			0 => Poll::Ready(()),
+			_ => Poll::Pending,
+		}
+	}
+
+	/// Asynchronously waits for the counter to reach zero, with a timeout.
+	///
+	/// # Note
+	/// The timeout is driven by Tokio's timer, so this must be called from
+	/// within a Tokio runtime with the time driver enabled.
+	///
+	/// # Returns
+	/// * [`WaitResult::Completed`] if the count reached zero.
+	/// * [`WaitResult::TimedOut`] if the timeout was reached before the count
+	///   reached zero.
+	#[cfg(feature = "async")]
+	pub async fn wait_async_timeout(&self, timeout: Duration) -> WaitResult {
+		WaitResult::new(
+			tokio::time::timeout(timeout, self.wait_async())
+				.await
+				.is_ok(),
+		)
+	}
+
+	/// Asynchronously waits for the counter to reach zero, or for `token` to
+	/// be cancelled, whichever comes first.
+	///
+	/// This fits in with Tokio services that already pass a
+	/// [`CancellationToken`](tokio_util::sync::CancellationToken) around for
+	/// cooperative shutdown.
+	///
+	/// # Returns
+	/// * [`WaitResult::Completed`] if the count reached zero. This takes
+	///   priority if the token is cancelled at the same time.
+	/// * [`WaitResult::Interrupted`] if the token was cancelled first.
+	///
+	/// ```rust
+	/// # #[tokio::main(flavor = "current_thread")]
+	/// # async fn main() {
+	/// use thread_counter::{ThreadCounter, WaitResult};
+	/// use tokio_util::sync::CancellationToken;
+	///
+	/// let counter = ThreadCounter::default();
+	/// let token = CancellationToken::new();
+	/// let _ticket = counter.ticket().unwrap();
+	/// tokio::spawn({
+	/// 	let token = token.clone();
+	/// 	async move { token.cancel() }
+	/// });
+	/// let result = counter.wait_async_cancellable(&token).await;
+	/// assert_eq!(result, WaitResult::Interrupted);
+	/// # }
+	/// ```
+	#[cfg(feature = "tokio-util")]
+	pub async fn wait_async_cancellable(
+		&self,
+		token: &tokio_util::sync::CancellationToken,
+	) -> WaitResult {
+		use core::future::Future;
+
+		let mut drained = std::pin::pin!(self.wait_async());
+		let mut cancelled = std::pin::pin!(token.cancelled());
+		core::future::poll_fn(|cx| {
+			if drained.as_mut().poll(cx).is_ready() {
+				Poll::Ready(WaitResult::Completed)
+			} else if cancelled.as_mut().poll(cx).is_ready() {
+				Poll::Ready(WaitResult::Interrupted)
+			} else {
+				Poll::Pending
+			}
+		})
+		.await
+	}
+
+	/// Returns the number of threads currently blocked waiting on this
+	/// counter.
+	///
+	/// This includes threads blocked in [`ThreadCounter::ticket()`] waiting for
+	/// the count to drop below the counter's capacity.
+	///
+	/// This is mostly useful for diagnostics, such as detecting shutdown logic
+	/// that leaks waiters, or a counter that nobody is actually waiting on.
+	///
+	/// # Note
+	/// Just like [`RawThreadCounter::count()`], the returned value is only a
+	/// snapshot, and may already be stale by the time it is returned.
+	pub fn waiters(&self) -> usize {
+		self.waiters.load(Ordering::SeqCst)
+	}
+
+	/// Checks whether the counter has reached zero, without blocking.
+	///
+	/// Unlike [`RawThreadCounter::wait()`], this never parks the current
+	/// thread, nor takes a lock, so it's suitable for polling the counter from
+	/// a hot loop.
+	///
+	/// # Returns
+	/// * `true` if the count is zero.
+	/// * `false` if there are still operations pending.
+	pub fn try_wait(&self) -> bool {
+		self.count() == 0
+	}
+
+	/// Waits for the counter to drop to `target` or below, with an optional
+	/// timeout.
+	///
+	/// # Arguments
+	/// * `target` - The count to wait for. Returns once the count is less than
+	///   or equal to this value.
+	/// * `timeout` - An optional duration to wait. If `None`, waits
+	///   indefinitely.
+	///
+	/// # Returns
+	/// * [`WaitResult::Completed`] if the count dropped to `target` or below.
+	/// * [`WaitResult::TimedOut`] if the timeout was reached before the count
+	///   dropped to `target` or below.
+	/// * [`WaitResult::Interrupted`] if waiting was cut short by
+	///   [`RawThreadCounter::notify_all()`].
+	///
+	/// The result always reflects the count actually observed when waiting
+	/// ended, even without a timeout: spurious wakeups are waited out, and
+	/// [`WaitResult::Completed`] is only returned if the count really is at or
+	/// below `target`.
+	///
+	/// ```rust
+	/// use std::thread;
+	/// use thread_counter::{ThreadCounter, WaitResult};
+	///
+	/// let counter = ThreadCounter::with_count(3);
+	/// let waiter = thread::spawn({
+	/// 	let counter = counter.clone();
+	/// 	move || counter.wait_for(1, None)
+	/// });
+	/// while counter.waiters() == 0 {
+	/// 	thread::yield_now();
+	/// }
+	///
+	/// // Waking the waiter up doesn't make it think the target was reached.
+	/// counter.notify_all();
+	/// assert_eq!(waiter.join().unwrap(), WaitResult::Interrupted);
+	/// assert_eq!(counter.count(), 3);
+	/// ```
+	///
+	/// The timeout is turned into a deadline up front, so however often the
+	/// waiter is woken up by changes that don't reach the target, it still
+	/// waits for the full duration:
+	///
+	/// ```rust
+	/// use std::{
+	/// 	sync::atomic::{AtomicBool, Ordering},
+	/// 	thread,
+	/// 	time::{Duration, Instant},
+	/// };
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::with_count(1);
+	/// let done = AtomicBool::new(false);
+	/// thread::scope(|scope| {
+	/// 	scope.spawn(|| {
+	/// 		// Keep waking the waiter up, without ever reaching zero.
+	/// 		while !done.load(Ordering::Relaxed) {
+	/// 			counter.increment();
+	/// 			counter.decrement();
+	/// 		}
+	/// 	});
+	///
+	/// 	let timeout = Duration::from_millis(100);
+	/// 	let start = Instant::now();
+	/// 	assert!(counter.wait_for(0, timeout).is_timed_out());
+	/// 	assert!(start.elapsed() >= timeout);
+	/// 	done.store(true, Ordering::Relaxed);
+	/// });
+	/// ```
+	#[cfg(feature = "std")]
+	pub fn wait_for(&self, target: usize, timeout: impl Into<Option<Duration>>) -> WaitResult {
+		self.wait_while(|count| count > target, timeout)
+	}
+
+	/// Waits while `condition` holds for the count, with an optional timeout.
+	///
+	/// This is the most general form of waiting, for when the completion
+	/// condition is something other than the count dropping to a threshold.
+	/// `condition` is called with the current count, and re-checked every
+	/// time the count decreases.
+	///
+	/// # Arguments
+	/// * `condition` - Returns `true` while the current thread should keep
+	///   waiting.
+	/// * `timeout` - An optional duration to wait. If `None`, waits
+	///   indefinitely.
+	///
+	/// # Returns
+	/// * [`WaitResult::Completed`] if `condition` stopped holding.
+	/// * [`WaitResult::TimedOut`] if the timeout was reached while `condition`
+	///   still held.
+	/// * [`WaitResult::Interrupted`] if waiting was cut short by
+	///   [`RawThreadCounter::notify_all()`].
+	///
+	/// ```rust
+	/// use std::time::Duration;
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// counter.increment_by(3);
+	/// let odd = |count| count % 2 == 1;
+	/// assert!(counter.wait_while(odd, Duration::ZERO).is_timed_out());
+	/// counter.decrement();
+	/// assert!(counter.wait_while(odd, Duration::ZERO).is_completed());
+	/// ```
+	#[cfg(feature = "std")]
+	pub fn wait_while(
+		&self,
+		condition: impl FnMut(usize) -> bool,
+		timeout: impl Into<Option<Duration>>,
+	) -> WaitResult {
+		self.block_while(condition, deadline_after(timeout.into()))
+			.0
+	}
+
+	/// Waits for the counter to reach zero, with an optional timeout, calling
+	/// `progress` with the current count every `interval` until it does.
+	///
+	/// This gives some visibility into long or stuck shutdowns, e.g. by
+	/// logging "still waiting, N remaining" every second, without needing a
+	/// separate monitoring thread. `progress` isn't called if the count
+	/// reaches zero before the first interval is up.
+	///
+	/// # Arguments
+	/// * `interval` - How long to wait between calls to `progress`.
+	/// * `progress` - Called with the current count each time an interval
+	///   passes without the count reaching zero.
+	/// * `timeout` - An optional duration to wait overall. If `None`, waits
+	///   indefinitely.
+	///
+	/// # Panics
+	/// Panics if `interval` is zero.
+	///
+	/// # Returns
+	/// Just like [`RawThreadCounter::wait()`].
+	///
+	/// ```rust
+	/// use std::time::Duration;
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::with_count(3);
+	/// let mut reports = Vec::new();
+	/// let result = counter.wait_with_progress(
+	/// 	Duration::from_millis(10),
+	/// 	|remaining| reports.push(remaining),
+	/// 	Duration::from_millis(35),
+	/// );
+	/// assert!(result.is_timed_out());
+	/// assert!(reports.iter().all(|&remaining| remaining == 3));
+	/// ```
+	#[cfg(feature = "std")]
+	pub fn wait_with_progress(
+		&self,
+		interval: Duration,
+		mut progress: impl FnMut(usize),
+		timeout: impl Into<Option<Duration>>,
+	) -> WaitResult {
+		assert!(!interval.is_zero(), "progress interval must be nonzero");
+		self.block_with_progress(
+			|count| count > 0,
+			deadline_after(timeout.into()),
+			interval,
+			&mut progress,
+		)
+	}
+
+	/// Waits for the count to stay at zero for `quiet` without interruption,
+	/// with an optional overall timeout.
+	///
+	/// Unlike [`RawThreadCounter::wait()`], this doesn't act on brief lulls:
+	/// if any work starts during the quiet period, even if it finishes again
+	/// straight away, the count has to drop back to zero and the quiet period
+	/// starts over. This is useful for detecting that a service has truly
+	/// gone idle.
+	///
+	/// # Arguments
+	/// * `quiet` - How long the count has to stay at zero.
+	/// * `timeout` - An optional duration to wait overall. If `None`, waits
+	///   indefinitely.
+	///
+	/// # Returns
+	/// * [`WaitResult::Completed`] if the count stayed at zero for `quiet`.
+	/// * [`WaitResult::TimedOut`] if the timeout was reached first.
+	/// * [`WaitResult::Interrupted`] if waiting was cut short by
+	///   [`RawThreadCounter::notify_all()`].
+	///
+	/// ```rust
+	/// use std::time::{Duration, Instant};
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// let quiet = Duration::from_millis(50);
+	/// let start = Instant::now();
+	/// assert!(counter.wait_quiescent(quiet, None).is_completed());
+	/// assert!(start.elapsed() >= quiet);
+	///
+	/// let _ticket = counter.ticket().unwrap();
+	/// let result = counter.wait_quiescent(quiet, Duration::from_millis(20));
+	/// assert!(result.is_timed_out());
+	/// ```
+	#[cfg(feature = "std")]
+	pub fn wait_quiescent(
+		&self,
+		quiet: Duration,
+		timeout: impl Into<Option<Duration>>,
+	) -> WaitResult {
+		let deadline = deadline_after(timeout.into());
+		loop {
+			let (result, _) = self.block_while(|count| count > 0, deadline);
+			if !result.is_completed() {
+				return result;
+			}
+			// Work starting bumps the generation, so even work that finished
+			// before this thread got to see it restarts the quiet period.
+			let generation = self.generation();
+			let quiet_end = deadline_after(Some(quiet));
+			let window = match quiet_end {
+				Some(quiet_end) => {
+					Some(deadline.map_or(quiet_end, |deadline| deadline.min(quiet_end)))
+				}
+				None => deadline,
+			};
+			let (result, _) = self.block_while(
+				|count| count == 0 && self.generation() == generation,
+				window,
+			);
+			match result {
+				WaitResult::Completed => continue,
+				WaitResult::TimedOut if has_passed(quiet_end) => return WaitResult::Completed,
+				result => return result,
+			}
+		}
+	}
+
+	/// Waits for the count to become nonzero, with an optional timeout.
+	///
+	/// This is the mirror image of [`RawThreadCounter::wait()`], for threads
+	/// that should sleep until there's at least one operation in flight, such
+	/// as a monitoring thread that only has work to do while the counter is
+	/// busy.
+	///
+	/// # Arguments
+	/// * `timeout` - An optional duration to wait. If `None`, waits
+	///   indefinitely.
+	///
+	/// # Returns
+	/// * [`WaitResult::Completed`] if the count became nonzero.
+	/// * [`WaitResult::TimedOut`] if the timeout was reached while the count
+	///   was still zero.
+	/// * [`WaitResult::Interrupted`] if waiting was cut short by
+	///   [`RawThreadCounter::notify_all()`].
+	///
+	/// ```rust
+	/// use std::thread;
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// let monitor = thread::spawn({
+	/// 	let counter = counter.clone();
+	/// 	move || counter.wait_nonzero(None)
+	/// });
+	/// while counter.waiters() == 0 {
+	/// 	thread::yield_now();
+	/// }
+	///
+	/// let _ticket = counter.ticket().unwrap();
+	/// assert!(monitor.join().unwrap().is_completed());
+	/// ```
+	#[cfg(feature = "std")]
+	pub fn wait_nonzero(&self, timeout: impl Into<Option<Duration>>) -> WaitResult {
+		self.block_while(|count| count == 0, deadline_after(timeout.into()))
+			.0
+	}
+
+	/// Waits for the count to rise to `n` or above, with an optional timeout.
+	///
+	/// This is the mirror image of [`RawThreadCounter::wait_for()`], for
+	/// threads that should wait until enough operations are in flight, such
+	/// as a coordinator that only starts a phase once `n` workers have taken
+	/// their tickets.
+	///
+	/// # Arguments
+	/// * `n` - The count to wait for. Returns once the count is greater than or
+	///   equal to this value.
+	/// * `timeout` - An optional duration to wait. If `None`, waits
+	///   indefinitely.
+	///
+	/// # Returns
+	/// * [`WaitResult::Completed`] if the count rose to `n` or above.
+	/// * [`WaitResult::TimedOut`] if the timeout was reached while the count
+	///   was still below `n`.
+	/// * [`WaitResult::Interrupted`] if waiting was cut short by
+	///   [`RawThreadCounter::notify_all()`].
+	///
+	/// ```rust
+	/// use std::thread;
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// let coordinator = thread::spawn({
+	/// 	let counter = counter.clone();
+	/// 	move || counter.wait_for_at_least(3, None)
+	/// });
+	/// while counter.waiters() == 0 {
+	/// 	thread::yield_now();
+	/// }
+	///
+	/// let _first = counter.ticket().unwrap();
+	/// let _second = counter.ticket().unwrap();
+	/// assert!(!coordinator.is_finished());
+	/// let _third = counter.ticket().unwrap();
+	/// assert!(coordinator.join().unwrap().is_completed());
+	/// ```
+	#[cfg(feature = "std")]
+	pub fn wait_for_at_least(&self, n: usize, timeout: impl Into<Option<Duration>>) -> WaitResult {
+		self.block_while(|count| count < n, deadline_after(timeout.into()))
+			.0
+	}
+
+	/// Waits for the count to differ from `from`, with an optional timeout.
+	///
+	/// This is meant for change-detection loops, which have sampled the count
+	/// and want to block until anything at all happens, whether the count goes
+	/// up or down.
+	///
+	/// # Note
+	/// If the count changes and then changes back before this thread gets a
+	/// chance to look, the change may be missed. Compare
+	/// [`RawThreadCounter::generation()`] to reliably detect new cycles of
+	/// work.
+	///
+	/// # Arguments
+	/// * `from` - The count to wait to change from. If the count already
+	///   differs, this returns straight away.
+	/// * `timeout` - An optional duration to wait. If `None`, waits
+	///   indefinitely.
+	///
+	/// # Returns
+	/// * [`WaitResult::Completed`] if the count differed from `from`.
+	/// * [`WaitResult::TimedOut`] if the timeout was reached while the count
+	///   was still `from`.
+	/// * [`WaitResult::Interrupted`] if waiting was cut short by
+	///   [`RawThreadCounter::notify_all()`].
+	///
+	/// ```rust
+	/// use std::thread;
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// let sampled = counter.count();
+	/// let watcher = thread::spawn({
+	/// 	let counter = counter.clone();
+	/// 	move || counter.wait_change(sampled, None)
+	/// });
+	/// counter.increment();
+	/// assert!(watcher.join().unwrap().is_completed());
+	/// ```
+	#[cfg(feature = "std")]
+	pub fn wait_change(&self, from: usize, timeout: impl Into<Option<Duration>>) -> WaitResult {
+		self.block_while(|count| count == from, deadline_after(timeout.into()))
+			.0
+	}
+
+	/// Waits for the counter to reach at least the given generation, with an
+	/// optional timeout.
+	///
+	/// Passing one more than the current [`RawThreadCounter::generation()`]
+	/// waits for a fresh cycle of work to start, even if the counter is
+	/// currently busy.
+	///
+	/// # Arguments
+	/// * `generation` - The generation to wait for.
+	/// * `timeout` - An optional duration to wait. If `None`, waits
+	///   indefinitely.
+	///
+	/// # Returns
+	/// * [`WaitResult::Completed`] if the generation was reached.
+	/// * [`WaitResult::TimedOut`] if the timeout was reached before the
+	///   generation was.
+	/// * [`WaitResult::Interrupted`] if waiting was cut short by
+	///   [`RawThreadCounter::notify_all()`].
+	///
+	/// ```rust
+	/// use std::thread;
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// let next = counter.generation() + 1;
+	/// let waiter = thread::spawn({
+	/// 	let counter = counter.clone();
+	/// 	move || counter.wait_for_generation(next, None)
+	/// });
+	/// drop(counter.ticket().unwrap());
+	/// assert!(waiter.join().unwrap().is_completed());
+	/// ```
+	#[cfg(feature = "std")]
+	pub fn wait_for_generation(
+		&self,
+		generation: u64,
+		timeout: impl Into<Option<Duration>>,
+	) -> WaitResult {
+		self.block_while(
+			|_| self.generation() < generation,
+			deadline_after(timeout.into()),
+		)
+		.0
+	}
+
+	/// Blocks the current thread while `condition` holds for the count, until
+	/// `deadline` passes, or until interrupted by
+	/// [`RawThreadCounter::notify_all()`].
+	///
+	/// The current thread is registered as a waiter for the whole time, so
+	/// that changes to the count will notify it. If the condition doesn't
+	/// hold to begin with, or the deadline has already passed, this returns
+	/// straight away, without touching the lock or condition variable.
+	///
+	/// The deadline is absolute rather than a duration, so that waking up
+	/// without the condition changing, whether spuriously or because the
+	/// count changed in some other way, never restarts or shortens the wait.
+	///
+	/// # Returns
+	/// Why waiting ended, along with the last count observed.
+	fn block_while(
+		&self,
+		condition: impl FnMut(usize) -> bool,
+		deadline: Option<Instant>,
+	) -> (WaitResult, usize) {
+		let interrupts = self.interrupts.load(Ordering::SeqCst);
+		self.block_while_since(condition, deadline, interrupts)
+	}
+
+	/// Like [`RawThreadCounter::block_while()`], but only interrupted by calls
+	/// to [`RawThreadCounter::notify_all()`] that bumped `interrupts` past the
+	/// given value.
+	///
+	/// The value has to be read before registering as a waiter. Otherwise, a
+	/// call to [`RawThreadCounter::notify_all()`] that sees this thread as
+	/// waiting, but bumps `interrupts` before it's read, would be missed, and
+	/// this thread would keep waiting.
+	fn block_while_since(
+		&self,
+		mut condition: impl FnMut(usize) -> bool,
+		deadline: Option<Instant>,
+		interrupts: usize,
+	) -> (WaitResult, usize) {
+		let count = self.count();
+		if !condition(count) {
+			return (WaitResult::Completed, count);
+		}
+		if has_passed(deadline) {
+			return (WaitResult::TimedOut, count);
+		}
+		let _waiter = WaiterGuard::new(&self.waiters);
+		let mut guard = self.lock.lock();
+		loop {
+			let count = self.count();
+			if !condition(count) {
+				return (WaitResult::Completed, count);
+			}
+			if self.interrupts.load(Ordering::SeqCst) != interrupts {
+				return (WaitResult::Interrupted, count);
+			}
+			match deadline {
+				Some(deadline) => {
+					if self.condvar.wait_until(&mut guard, deadline) {
+						// The count may have changed right as we timed out.
+						let count = self.count();
+						return match condition(count) {
+							true => (WaitResult::TimedOut, count),
+							false => (WaitResult::Completed, count),
+						};
+					}
+				}
+				None => self.condvar.wait(&mut guard),
+			}
+		}
+	}
+
+	/// Like [`RawThreadCounter::block_while()`], but calls `progress` with
+	/// the current count every `interval` until waiting ends.
+	#[cfg(feature = "std")]
+	fn block_with_progress(
+		&self,
+		mut condition: impl FnMut(usize) -> bool,
+		deadline: Option<Instant>,
+		interval: Duration,
+		progress: &mut dyn FnMut(usize),
+	) -> WaitResult {
+		// Read this once up front, so that an interrupt landing in between two
+		// progress reports still ends the wait.
+		let interrupts = self.interrupts.load(Ordering::SeqCst);
+		loop {
+			let next = match deadline_after(Some(interval)) {
+				Some(next) => Some(deadline.map_or(next, |deadline| deadline.min(next))),
+				None => deadline,
+			};
+			let (result, count) = self.block_while_since(&mut condition, next, interrupts);
+			if !result.is_timed_out() || has_passed(deadline) {
+				return result;
+			}
+			progress(count);
+		}
+	}
+
+	/// Atomically replaces the count with `f(count)`, returning the previous
+	/// count and the new one.
+	///
+	/// The new count is capped at [`MAX_COUNT`], and whether the counter is
+	/// closed is left as it is.
+	fn update(&self, mut f: impl FnMut(usize) -> usize) -> (usize, usize) {
+		let word = match self
+			.count
+			.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |word| {
+				Some(with_count_bits(word, &mut f))
+			}) {
+			Ok(word) | Err(word) => word,
+		};
+		(word & MAX_COUNT, with_count_bits(word, f) & MAX_COUNT)
+	}
+
+	/// Handles the count having been increased from `previous` to `count`,
+	/// updating the peak, starting a new generation and running activation
+	/// callbacks if the counter was empty, and notifying any waiters.
+	fn increased(&self, previous: usize, count: usize) {
+		// Saturated, or incremented by zero.
+		if previous == count {
+			return;
+		}
+		#[cfg(feature = "tracing")]
+		tracing::trace!(previous, count, "thread counter incremented");
+		#[cfg(feature = "std")]
+		self.touch();
+		self.peak.fetch_max(count, Ordering::Relaxed);
+		let activated = previous == 0 && count > 0;
+		if activated {
+			self.generation.fetch_add(1, Ordering::SeqCst);
+		}
+		atomic::store_load_fence();
+		if self.waiters() > 0 {
+			self.wake_waiters();
+		}
+		#[cfg(feature = "async")]
+		self.publish_change();
+		#[cfg(feature = "metrics")]
+		if let Some(gauge) = &self.gauge {
+			gauge.increment((count - previous) as f64);
+		}
+		if let Some(high_water) = &self.high_water {
+			high_water.increased(count);
+		}
+		if activated && self.activate_callback_count.load(Ordering::SeqCst) > 0 {
+			run_callbacks(&self.activate_callbacks);
+		}
+	}
+
+	/// Increments the thread counter by `n`, first blocking until there's room
+	/// for them below the counter's capacity, if it has one.
+	///
+	/// Fails without changing the count if the counter is closed.
+	///
+	/// # Panics
+	/// Panics if `n` is greater than the counter's capacity, as there could
+	/// never be room for it.
+	fn acquire(&self, n: usize) -> Result<(), Closed> {
+		self.acquire_until(n, None).map(|_| ())
+	}
+
+	/// Like [`RawThreadCounter::acquire()`], but gives up once `deadline`
+	/// passes, returning `Ok(false)` without changing the count.
+	fn acquire_until(&self, n: usize, deadline: Option<Instant>) -> Result<bool, Closed> {
+		let Some(capacity) = self.capacity else {
+			// Without a capacity, there's always room, so this can only fail
+			// because the counter is closed.
+			return match self.take_room(n) {
+				true => Ok(true),
+				false => Err(Closed),
+			};
+		};
+		assert!(
+			n <= capacity,
+			"cannot take more tickets than the thread counter's capacity"
+		);
+		if self.fair {
+			return self.acquire_fair(n, capacity, deadline);
+		}
+		loop {
+			if self.take_room(n) {
+				return Ok(true);
+			}
+			if self.is_closed() {
+				return Err(Closed);
+			}
+			if has_passed(deadline) {
+				return Ok(false);
+			}
+			self.block_while(|count| count > capacity - n && !self.is_closed(), deadline);
+		}
+	}
+
+	/// Like [`RawThreadCounter::acquire_until()`], but queues up behind any
+	/// threads that started waiting for capacity earlier, so that they're
+	/// served in FIFO order.
+	fn acquire_fair(
+		&self,
+		n: usize,
+		capacity: usize,
+		deadline: Option<Instant>,
+	) -> Result<bool, Closed> {
+		let turn = self.next_turn.fetch_add(1, Ordering::SeqCst);
+		loop {
+			if self.now_serving.load(Ordering::SeqCst) == turn && self.take_room(n) {
+				self.finish_turn(self.skipped_turns.lock());
+				return Ok(true);
+			}
+			// Once closed, nothing will ever be served again, so there's no
+			// need to give up the turn.
+			if self.is_closed() {
+				return Err(Closed);
+			}
+			if has_passed(deadline) {
+				let mut skipped_turns = self.skipped_turns.lock();
+				if self.now_serving.load(Ordering::SeqCst) == turn {
+					self.finish_turn(skipped_turns);
+				} else {
+					skipped_turns.push(turn);
+				}
+				return Ok(false);
+			}
+			self.block_while(
+				|count| {
+					!self.is_closed()
+						&& (self.now_serving.load(Ordering::SeqCst) != turn || count > capacity - n)
+				},
+				deadline,
+			);
+		}
+	}
+
+	/// Hands the turn over to the next thread waiting for capacity in fair
+	/// mode, skipping over any turns that were given up in the meantime.
+	///
+	/// This takes the lock on `skipped_turns`, so that a thread can't give up
+	/// its turn right as it's its turn.
+	fn finish_turn(&self, mut skipped_turns: MutexGuard<'_, Vec<usize>>) {
+		let mut serving = self.now_serving.load(Ordering::SeqCst).wrapping_add(1);
+		while let Some(index) = skipped_turns.iter().position(|&turn| turn == serving) {
+			skipped_turns.swap_remove(index);
+			serving = serving.wrapping_add(1);
+		}
+		self.now_serving.store(serving, Ordering::SeqCst);
+		drop(skipped_turns);
+		if self.waiters() > 0 {
+			self.wake_waiters();
+		}
+	}
+
+	/// Increments the thread counter by `n` if there's room for them below the
+	/// counter's capacity and the counter isn't closed, returning whether it
+	/// did so.
+	///
+	/// In fair mode, this never jumps ahead of threads already queued up for
+	/// capacity.
+	fn try_acquire(&self, n: usize) -> bool {
+		let queued =
+			self.now_serving.load(Ordering::SeqCst) != self.next_turn.load(Ordering::SeqCst);
+		if self.fair && queued {
+			return false;
+		}
+		self.take_room(n)
+	}
+
+	/// Increments the thread counter by `n` if there's room for them below the
+	/// counter's capacity and the counter isn't closed, returning whether it
+	/// did so, regardless of whose turn it is in fair mode.
+	fn take_room(&self, n: usize) -> bool {
+		let has_room = |count: usize| {
+			self.capacity
+				.is_none_or(|capacity| count.checked_add(n).is_some_and(|count| count <= capacity))
+		};
+		match self
+			.count
+			.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |word| {
+				(word & CLOSED == 0 && has_room(word))
+					.then(|| word.saturating_add(n).min(MAX_COUNT))
+			}) {
+			Ok(previous) => {
+				self.increased(previous, previous.saturating_add(n).min(MAX_COUNT));
+				true
+			}
+			Err(_) => false,
+		}
+	}
+
+	/// Handles the count having been decreased from `previous` to `count`,
+	/// notifying any waiters, and running the drain callbacks if the counter
+	/// was drained.
+	///
+	/// Returns whether the counter was drained.
+	fn changed(&self, previous: usize, count: usize) -> bool {
+		// Already zero, or decremented by zero.
+		if previous == count {
+			return false;
+		}
+		#[cfg(feature = "tracing")]
+		tracing::trace!(previous, count, "thread counter decremented");
+		#[cfg(feature = "std")]
+		self.touch();
+		#[cfg(feature = "metrics")]
+		if let Some(gauge) = &self.gauge {
+			gauge.decrement((previous - count) as f64);
+		}
+		if let Some(high_water) = &self.high_water {
+			high_water.decreased(count);
+		}
+		self.notify_changed(count);
+		let drained = previous > 0 && count == 0;
+		if drained {
+			self.run_drain_callbacks();
+		}
+		drained
+	}
+
+	/// Records that the count has just changed, for
+	/// [`RawThreadCounter::idle_for()`], if that's being tracked.
+	#[cfg(feature = "std")]
+	fn touch(&self) {
+		if !self.track_idle {
+			return;
+		}
+		// Zero means the count never changed, so make sure not to store it.
+		let nanos = u64::try_from(epoch().elapsed().as_nanos()).unwrap_or(u64::MAX);
+		self.last_change.store(nanos.max(1), Ordering::Relaxed);
+	}
+
+	/// Runs all callbacks registered with [`RawThreadCounter::on_drain()`],
+	/// and notifies all channels from [`RawThreadCounter::subscribe()`].
+	fn run_drain_callbacks(&self) {
+		if self.drain_callback_count.load(Ordering::SeqCst) > 0 {
+			run_callbacks(&self.drain_callbacks);
+		}
+		#[cfg(feature = "std")]
+		if self.subscriber_count.load(Ordering::SeqCst) > 0 {
+			let mut subscribers = self.subscribers.lock();
+			subscribers.retain(|subscriber| subscriber.send(()).is_ok());
+			self.subscriber_count
+				.store(subscribers.len(), Ordering::SeqCst);
+		}
+	}
+
+	/// Records that `n` operations were abandoned by a panicking thread,
+	/// running the callbacks registered with [`RawThreadCounter::on_panic()`].
+	#[cfg(feature = "std")]
+	fn record_panic(&self, n: usize) {
+		self.panicked.fetch_add(n, Ordering::SeqCst);
+		run_callbacks(&self.panic_callbacks);
+	}
+
+	/// Writes the fields shown by the [`fmt::Debug`] implementations.
+	///
+	/// None of these require taking a lock, so formatting never blocks.
+	fn debug_fields(&self, f: &mut fmt::DebugStruct<'_, '_>) -> fmt::Result {
+		f.field("count", &self.count())
+			.field("peak", &self.peak())
+			.field("generation", &self.generation())
+			.field("waiters", &self.waiters())
+			.field("capacity", &self.capacity)
+			.field("closed", &self.is_closed())
+			.finish()
+	}
+
+	/// Wakes up all waiting threads, if there are any, before the count has
+	/// changed, only returning once they've all been notified.
+	fn wake_waiters(&self) {
+		// Waiting threads hold the lock from checking their condition until
+		// they're parked, so taking it here ensures none of them can miss this
+		// notification.
+		drop(self.lock.lock());
+		self.condvar.notify_all();
+	}
+
+	/// Wakes up all waiting threads, if there are any, after the count has
+	/// changed to `count`.
+	fn notify_changed(&self, count: usize) {
+		atomic::store_load_fence();
+		if self.waiters() > 0 {
+			self.wake_waiters();
+		}
+		#[cfg(feature = "async")]
+		{
+			if count == 0 {
+				self.drained.notify_waiters();
+				if self.pending_wakers.load(Ordering::SeqCst) > 0 {
+					// Take the wakers out first, in case waking one polls again.
+					let wakers = {
+						let mut wakers = self.wakers.lock();
+						self.pending_wakers.store(0, Ordering::SeqCst);
+						core::mem::take(&mut *wakers)
+					};
+					wakers.into_iter().for_each(Waker::wake);
+				}
+			}
+			self.publish_change();
+		}
+		#[cfg(not(feature = "async"))]
+		let _ = count;
+	}
+}
+
+impl fmt::Debug for RawThreadCounter {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.debug_fields(&mut f.debug_struct("RawThreadCounter"))
+	}
+}
+
+/// Creates a fresh, fully independent counter, starting at this counter's
+/// current count.
+///
+/// Only the count is carried over, just like going through
+/// [`RawThreadCounter::snapshot()`]. The clone doesn't share waiters, drain
+/// callbacks or a capacity with the original, and changes to either aren't
+/// seen by the other. To share a counter instead, use [`ThreadCounter`],
+/// whose clones all refer to the same counter.
+///
+/// ```rust
+/// use thread_counter::RawThreadCounter;
+///
+/// let original = RawThreadCounter::with_count(2);
+/// let copy = original.clone();
+/// original.decrement();
+/// assert_eq!(original.count(), 1);
+/// assert_eq!(copy.count(), 2);
+/// ```
+impl Clone for RawThreadCounter {
+	fn clone(&self) -> Self {
+		Self::with_count(self.count())
+	}
+}
+
+impl Drop for RawThreadCounter {
+	fn drop(&mut self) {
+		let count = self.count();
+		if count > 0 {
+			if let Some(callback) = &self.leak_callback {
+				callback(count);
+			}
+			// Panicking while already unwinding would abort, hiding the
+			// original panic.
+			#[cfg(feature = "std")]
+			let panicking = std::thread::panicking();
+			#[cfg(not(feature = "std"))]
+			let panicking = false;
+			debug_assert!(
+				!self.expect_zero_on_drop || panicking,
+				"thread counter dropped with a nonzero count of {count}"
+			);
+		}
+	}
+}
+
+impl Default for RawThreadCounter {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// A callback registered on a [`RawThreadCounter`].
+type Callback = Arc<dyn Fn() + Send + Sync>;
+
+/// A callback registered on a [`RawThreadCounter`], which is given the count.
+type CountCallback = Box<dyn Fn(usize) + Send + Sync>;
+
+/// A callback registered on a [`RawThreadCounter`], which is given a duration.
+#[cfg(feature = "std")]
+type DurationCallback = Box<dyn Fn(Duration) + Send + Sync>;
+
+/// A callback registered with [`ThreadCounterBuilder::on_high_water()`].
+struct HighWater {
+	/// The count at which the callback fires.
+	threshold: usize,
+	callback: CountCallback,
+	/// Cleared when the callback fires, and set again once the count has
+	/// dropped back down to half the threshold, so that a count hovering
+	/// around the threshold only fires it once.
+	armed: AtomicBool,
+}
+
+impl HighWater {
+	fn new(threshold: usize, callback: CountCallback) -> Self {
+		Self {
+			threshold,
+			callback,
+			armed: AtomicBool::new(true),
+		}
+	}
+
+	/// Runs the callback if `count` has reached the threshold, and it hasn't
+	/// already fired since the count was last low enough.
+	fn increased(&self, count: usize) {
+		if count >= self.threshold && self.armed.swap(false, Ordering::Relaxed) {
+			(self.callback)(count);
+		}
+	}
+
+	/// Re-arms the callback once `count` has dropped low enough.
+	fn decreased(&self, count: usize) {
+		if count <= self.threshold / 2 && !self.armed.load(Ordering::Relaxed) {
+			self.armed.store(true, Ordering::Relaxed);
+		}
+	}
+}
+
+/// Applies `f` to the count in a [`RawThreadCounter`]'s count word, capping the
+/// result at [`MAX_COUNT`] and keeping the [`CLOSED`] bit as it is.
+fn with_count_bits(word: usize, f: impl FnOnce(usize) -> usize) -> usize {
+	(word & CLOSED) | f(word & MAX_COUNT).min(MAX_COUNT)
+}
+
+/// Runs all of the given callbacks, in the order they were registered.
+fn run_callbacks(callbacks: &Mutex<Vec<Callback>>) {
+	// Clone the callbacks out first, so that they can register more callbacks
+	// without deadlocking.
+	let callbacks = callbacks.lock().clone();
+	for callback in callbacks {
+		callback();
+	}
+}
+
+/// The outcome of waiting on a thread counter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WaitResult {
+	/// The awaited condition was reached, e.g. the count reached zero.
+	Completed,
+	/// The timeout or deadline was reached before the awaited condition.
+	TimedOut,
+	/// Waiting was cut short by [`RawThreadCounter::notify_all()`] before the
+	/// awaited condition was reached.
+	Interrupted,
+}
+
+impl WaitResult {
+	#[cfg(feature = "async")]
+	fn new(completed: bool) -> Self {
+		if completed {
+			Self::Completed
+		} else {
+			Self::TimedOut
+		}
+	}
+
+	/// Returns `true` if the awaited condition was reached.
+	pub fn is_completed(self) -> bool {
+		self == Self::Completed
+	}
+
+	/// Returns `true` if the timeout or deadline was reached first.
+	pub fn is_timed_out(self) -> bool {
+		self == Self::TimedOut
+	}
+
+	/// Returns `true` if waiting was cut short by
+	/// [`RawThreadCounter::notify_all()`].
+	pub fn is_interrupted(self) -> bool {
+		self == Self::Interrupted
+	}
+
+	/// Converts this into a [`Result`], with `err` if the wait didn't
+	/// complete, whether because it timed out or because it was interrupted.
+	///
+	/// This makes it easy to bail out of fallible functions with `?`.
+	///
+	/// ```rust
+	/// # #[cfg(feature = "std")]
+	/// # {
+	/// use std::time::Duration;
+	/// use thread_counter::ThreadCounter;
+	///
+	/// #[derive(Debug, PartialEq)]
+	/// struct Timeout;
+	///
+	/// fn shutdown(counter: &ThreadCounter) -> Result<(), Timeout> {
+	/// 	counter.wait(Duration::ZERO).ok_or(Timeout)?;
+	/// 	// Clean up...
+	/// 	Ok(())
+	/// }
+	///
+	/// let counter = ThreadCounter::default();
+	/// assert_eq!(shutdown(&counter), Ok(()));
+	/// let _ticket = counter.ticket().unwrap();
+	/// assert_eq!(shutdown(&counter), Err(Timeout));
+	/// # }
+	/// ```
+	pub fn ok_or<E>(self, err: E) -> Result<(), E> {
+		match self {
+			Self::Completed => Ok(()),
+			_ => Err(err),
+		}
+	}
+
+	/// Converts this into a [`Result`], calling `f` with the result to make
+	/// the error if the wait didn't complete.
+	///
+	/// This is like [`WaitResult::ok_or()`], but lets the error tell timing
+	/// out and being interrupted apart.
+	pub fn ok_or_else<E>(self, f: impl FnOnce(Self) -> E) -> Result<(), E> {
+		match self {
+			Self::Completed => Ok(()),
+			result => Err(f(result)),
+		}
+	}
+
+	/// Returns `Some(f())` if the wait completed, and `None` otherwise, just
+	/// like [`bool::then()`].
+	pub fn then<T>(self, f: impl FnOnce() -> T) -> Option<T> {
+		self.is_completed().then(f)
+	}
+
+	/// Waits again with `f` if the wait completed, or returns this result
+	/// otherwise.
+	///
+	/// This chains waits that should all complete, stopping at the first one
+	/// that doesn't.
+	///
+	/// ```rust
+	/// # #[cfg(feature = "std")]
+	/// # {
+	/// use std::time::Duration;
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let readers = ThreadCounter::default();
+	/// let writers = ThreadCounter::default();
+	/// let _writer = writers.ticket().unwrap();
+	///
+	/// let result = readers
+	/// 	.wait(Duration::ZERO)
+	/// 	.and_then(|| writers.wait(Duration::ZERO));
+	/// assert!(result.is_timed_out());
+	/// # }
+	/// ```
+	pub fn and_then(self, f: impl FnOnce() -> Self) -> Self {
+		match self {
+			Self::Completed => f(),
+			result => result,
+		}
+	}
+}
+
+/// Converts to `true` if the wait completed, and `false` if it timed out or
+/// was interrupted.
+impl From<WaitResult> for bool {
+	fn from(result: WaitResult) -> Self {
+		result.is_completed()
+	}
+}
+
+/// The error returned when trying to take a ticket from a counter that has
+/// been closed with [`RawThreadCounter::close()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Closed;
+
+impl fmt::Display for Closed {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("thread counter is closed")
+	}
+}
+
+impl core::error::Error for Closed {}
+
+/// Converts an optional timeout into a deadline, starting from now.
+///
+/// Timeouts too large to be represented as an [`Instant`] are treated as no
+/// timeout at all.
+#[cfg(feature = "std")]
+fn deadline_after(timeout: Option<Duration>) -> Option<Instant> {
+	timeout.and_then(|timeout| Instant::now().checked_add(timeout))
+}
+
+/// Returns the time that [`RawThreadCounter::idle_for()`] timestamps are
+/// measured from, which is whenever this was first called.
+#[cfg(feature = "std")]
+fn epoch() -> Instant {
+	static EPOCH: std::sync::OnceLock<Instant> = std::sync::OnceLock::new();
+	*EPOCH.get_or_init(Instant::now)
+}
+
+/// Returns whether `deadline` has passed, if there is one.
+fn has_passed(deadline: Option<Instant>) -> bool {
+	#[cfg(feature = "std")]
+	return deadline.is_some_and(|deadline| deadline <= Instant::now());
+	// Deadlines can't be constructed without `std`.
+	#[cfg(not(feature = "std"))]
+	return deadline.is_some();
+}
+
+/// Registers the current thread as a waiter for as long as it's alive.
+struct WaiterGuard<'a>(&'a AtomicUsize);
+
+impl<'a> WaiterGuard<'a> {
+	fn new(waiters: &'a AtomicUsize) -> Self {
+		waiters.fetch_add(1, Ordering::SeqCst);
+		atomic::store_load_fence();
+		Self(waiters)
+	}
+}
+
+impl Drop for WaiterGuard<'_> {
+	fn drop(&mut self) {
+		self.0.fetch_sub(1, Ordering::SeqCst);
+	}
+}
+
+/// A RAII guard for automatically managing the thread count.
+///
+/// When a `Ticket` is created, it increments the associated thread counter.
+/// When the `Ticket` is dropped, it automatically decrements the counter.
+#[must_use = "dropping the Ticket immediately decrements the counter"]
+pub struct Ticket {
+	counter: ThreadCounter,
+	/// How much to decrement the counter by when this ticket is dropped.
+	count: usize,
+	/// A span covering the lifetime of this ticket.
+	#[cfg(feature = "tracing")]
+	span: tracing::Span,
+}
+
+impl Ticket {
+	/// Wraps up `count` increments already made to `counter`.
+	fn new(counter: ThreadCounter, count: usize) -> Self {
+		Self {
+			#[cfg(feature = "tracing")]
+			span: tracing::trace_span!("ticket", count),
+			counter,
+			count,
+		}
+	}
+
+	/// Returns the [`ThreadCounter`] this ticket belongs to.
+	///
+	/// This can be used to read or wait on the counter when only the ticket is
+	/// in scope. Cloning the returned counter is cheap, as it's backed by an
+	/// [`Arc`].
+	pub fn counter(&self) -> &ThreadCounter {
+		&self.counter
+	}
+
+	/// Consumes this ticket *without* decrementing the counter.
+	///
+	/// This is an escape hatch for handing off responsibility for the
+	/// decrement to some other mechanism, which must then balance the count
+	/// manually with [`RawThreadCounter::decrement()`]. If nothing does, the
+	/// count is leaked, and [`RawThreadCounter::wait()`] will never see it
+	/// reach zero.
+	///
+	/// Unlike passing the ticket to [`std::mem::forget`], this still releases
+	/// the ticket's reference to the counter, so the counter itself isn't
+	/// leaked.
+	pub fn forget(mut self) {
+		self.count = 0;
+	}
+
+	/// Splits this ticket into two, the first counting for `n` operations,
+	/// and the second for the rest.
+	///
+	/// This leaves the count untouched, and is meant for tickets from
+	/// [`ThreadCounter::ticket_n()`], so that a share of a batch can be
+	/// handed off to a subtask, which then decrements the counter for just
+	/// its share when done.
+	///
+	/// # Panics
+	/// Panics if `n` is greater than the number of operations this ticket
+	/// counts for.
+	///
+	/// ```rust
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// let batch = counter.ticket_n(5).unwrap();
+	/// let (first, rest) = batch.split(2);
+	/// assert_eq!(counter.count(), 5);
+	///
+	/// drop(first);
+	/// assert_eq!(counter.count(), 3);
+	/// drop(rest);
+	/// assert_eq!(counter.count(), 0);
+	/// ```
+	pub fn split(mut self, n: usize) -> (Ticket, Ticket) {
+		assert!(
+			n <= self.count,
+			"can't split {n} operations off a ticket for {}",
+			self.count
+		);
+		let rest = core::mem::take(&mut self.count) - n;
+		(
+			Ticket::new(self.counter.clone(), n),
+			Ticket::new(self.counter.clone(), rest),
+		)
+	}
+
+	/// Returns the `tracing` span covering the lifetime of this ticket.
+	///
+	/// The span is opened at `TRACE` level when the ticket is created, and
+	/// closed when it's dropped. As a ticket may move between threads, it
+	/// isn't entered automatically; enter it (or use
+	/// [`tracing::Span::in_scope`]) wherever the ticket's work happens, so
+	/// that events are correlated with it.
+	#[cfg(feature = "tracing")]
+	pub fn span(&self) -> &tracing::Span {
+		&self.span
+	}
+}
+
+/// Cloning a ticket increments the counter again, and each clone decrements
+/// it when dropped, so the count stays balanced. A clone of a ticket from
+/// [`ThreadCounter::ticket_n()`] counts for just as much as the original.
+///
+/// As the work the ticket belongs to has already been admitted, cloning it
+/// never blocks or fails: it ignores the counter's capacity, which the count
+/// can then go over, and still works once the counter has been closed. Use
+/// [`ThreadCounter::ticket_n()`] instead to wait for room.
+///
+/// ```rust
+/// use thread_counter::ThreadCounter;
+///
+/// let counter = ThreadCounter::default();
+/// let ticket = counter.ticket().unwrap();
+/// let clones = vec![ticket.clone(); 4];
+/// assert_eq!(counter.count(), 5);
+///
+/// drop(clones);
+/// assert_eq!(counter.count(), 1);
+/// drop(ticket);
+/// assert_eq!(counter.count(), 0);
+///
+/// // Cloning doesn't wait for room below the capacity.
+/// let counter = ThreadCounter::with_capacity(1);
+/// let ticket = counter.ticket().unwrap();
+/// let clone = ticket.clone();
+/// assert_eq!(counter.count(), 2);
+/// ```
+impl Clone for Ticket {
+	fn clone(&self) -> Self {
+		self.counter.increment_by(self.count);
+		Ticket::new(self.counter.clone(), self.count)
+	}
+}
+
+/// Returns a handle to the counter the ticket belongs to, just like cloning
+/// [`Ticket::counter()`].
+impl From<&Ticket> for ThreadCounter {
+	fn from(ticket: &Ticket) -> Self {
+		ticket.counter.clone()
+	}
+}
+
+impl fmt::Debug for Ticket {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Ticket").finish_non_exhaustive()
+	}
+}
+
+impl Drop for Ticket {
+	fn drop(&mut self) {
+		if self.count > 0 {
+			#[cfg(feature = "std")]
+			if std::thread::panicking() {
+				self.counter.record_panic(self.count);
+			}
+			self.counter.decrement_by(self.count);
+		}
+	}
+}
+
+/// A [`Ticket`] that carries some user data along with it.
+///
+/// This dereferences to the contained data, and decrements the associated
+/// counter when dropped, just like a regular [`Ticket`].
+#[derive(Debug, Clone)]
+#[must_use = "dropping the DataTicket immediately decrements the counter"]
+pub struct DataTicket<T> {
+	ticket: Ticket,
+	data: T,
+}
+
+impl<T> DataTicket<T> {
+	/// Returns the underlying [`Ticket`].
+	pub fn ticket(&self) -> &Ticket {
+		&self.ticket
+	}
+
+	/// Splits this into the underlying [`Ticket`] and the contained data.
+	///
+	/// The counter is only decremented once the returned [`Ticket`] is dropped.
+	pub fn into_parts(self) -> (Ticket, T) {
+		(self.ticket, self.data)
+	}
+}
+
+impl<T> Deref for DataTicket<T> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.data
+	}
+}
+
+impl<T> DerefMut for DataTicket<T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.data
+	}
+}
+
+/// Runs a block while holding a [`Ticket`] from a [`ThreadCounter`].
+///
+/// `ticket_scope!(counter, { ... })` takes a ticket, runs the block, and
+/// drops the ticket once the block is done, however it's left, including
+/// by an early `return` from the surrounding function. It evaluates to
+/// `Ok` with the block's value, or to `Err(`[`Closed`]`)` without running
+/// the block if the counter has been [closed](RawThreadCounter::close()).
+///
+/// ```rust
+/// use thread_counter::{ticket_scope, Closed, ThreadCounter};
+///
+/// fn process(counter: &ThreadCounter, input: Option<u32>) -> Result<u32, Closed> {
+/// 	ticket_scope!(counter, {
+/// 		assert_eq!(counter.count(), 1);
+/// 		let Some(input) = input else {
+/// 			return Ok(0);
+/// 		};
+/// 		input * 2
+/// 	})
+/// }
+///
+/// let counter = ThreadCounter::default();
+/// assert_eq!(process(&counter, Some(21)), Ok(42));
+/// assert_eq!(process(&counter, None), Ok(0));
+/// assert!(counter.is_empty());
+///
+/// counter.close();
+/// assert_eq!(process(&counter, Some(21)), Err(Closed));
+/// ```
+#[macro_export]
+macro_rules! ticket_scope {
+	($counter:expr, $body:block) => {
+		match ($counter).ticket() {
+			::core::result::Result::Ok(_ticket) => ::core::result::Result::Ok($body),
+			::core::result::Result::Err(closed) => ::core::result::Result::Err(closed),
+		}
+	};
 }
+
+// Handing counters and tickets to other threads is the whole point, so make
+// sure that keeps compiling in every configuration.
+const _: () = {
+	const fn assert_send_sync<T: Send + Sync>() {}
+	assert_send_sync::<ThreadCounter>();
+	assert_send_sync::<RawThreadCounter>();
+	assert_send_sync::<WeakCounter>();
+	assert_send_sync::<WaitGroup>();
+	#[cfg(feature = "std")]
+	assert_send_sync::<ShardedThreadCounter>();
+	#[cfg(feature = "std")]
+	assert_send_sync::<ShardedTicket<'_>>();
+	#[cfg(feature = "std")]
+	assert_send_sync::<DrainGuard>();
+	#[cfg(feature = "std")]
+	assert_send_sync::<TimedTicket>();
+	#[cfg(feature = "atomic-wait")]
+	assert_send_sync::<AtomicWaitCounter>();
+	#[cfg(feature = "atomic-wait")]
+	assert_send_sync::<AtomicWaitTicket<'_>>();
+	assert_send_sync::<CounterView>();
+	assert_send_sync::<CounterGroup>();
+	assert_send_sync::<Ticket>();
+	assert_send_sync::<DataTicket<()>>();
+	#[cfg(feature = "async")]
+	assert_send_sync::<Changes<'_>>();
+};
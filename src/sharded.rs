@@ -0,0 +1,250 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+use crate::{
+	deadline_after, has_passed,
+	sync::{
+		atomic::{self, AtomicU64, AtomicUsize, Ordering},
+		Condvar, Mutex,
+	},
+	WaitResult, WaiterGuard,
+};
+use alloc::{boxed::Box, vec::Vec};
+use core::{fmt, time::Duration};
+use std::thread;
+
+/// A thread counter that spreads its count across several shards, for
+/// extreme rates of taking and dropping tickets from many threads at once.
+///
+/// With a regular [`ThreadCounter`](crate::ThreadCounter), every ticket
+/// taken or dropped writes to the same atomic, so with enough cores hammering
+/// it, the cache line it lives on becomes the bottleneck. Here, each thread
+/// is assigned to one of several shards, each on its own cache line, so that
+/// threads mostly don't contend with each other at all.
+///
+/// # Performance
+/// The trade-off is that reading the count, and waiting for it to reach
+/// zero, have to visit every shard, so they're proportionally slower. While
+/// any thread is waiting, every dropped ticket also wakes it up to re-check
+/// the count, as telling whether a ticket was the last one would mean
+/// visiting every shard on every drop. As such, this is only worth it when
+/// tickets are taken far more often than the counter is waited on.
+///
+/// Tickets borrow the counter rather than sharing ownership of it, as even
+/// the reference count of an [`Arc`](alloc::sync::Arc) would be a single
+/// point of contention. Use scoped threads, or a `static` counter, to hand
+/// tickets to other threads.
+///
+/// ```rust
+/// use std::thread;
+/// use thread_counter::ShardedThreadCounter;
+///
+/// let counter = ShardedThreadCounter::default();
+/// thread::scope(|scope| {
+/// 	for _ in 0..4 {
+/// 		scope.spawn(|| {
+/// 			for _ in 0..1000 {
+/// 				let _ticket = counter.ticket();
+/// 			}
+/// 		});
+/// 	}
+/// 	counter.wait(None);
+/// });
+/// assert!(counter.is_empty());
+/// ```
+pub struct ShardedThreadCounter {
+	shards: Box<[Shard]>,
+	/// The number of threads currently waiting on the condition variable.
+	waiters: AtomicUsize,
+	lock: Mutex<()>,
+	condvar: Condvar,
+}
+
+/// A single shard of a [`ShardedThreadCounter`].
+///
+/// Rather than a count that goes up and down, this keeps separate running
+/// totals of tickets taken and dropped, which only ever go up. This is what
+/// lets the count be read consistently across shards: each shard can be
+/// read at a different moment, while other threads are busy taking and
+/// dropping tickets, without ever seeing a false zero.
+#[repr(align(128))]
+struct Shard {
+	/// The number of tickets ever taken from this shard.
+	taken: AtomicU64,
+	/// The number of tickets ever dropped from this shard.
+	dropped: AtomicU64,
+}
+
+impl ShardedThreadCounter {
+	/// Creates a new counter with one shard per available CPU.
+	pub fn new() -> Self {
+		Self::with_shards(thread::available_parallelism().map_or(1, |cpus| cpus.get()))
+	}
+
+	/// Creates a new counter with `shards` shards.
+	///
+	/// # Panics
+	/// Panics if `shards` is zero.
+	pub fn with_shards(shards: usize) -> Self {
+		assert!(
+			shards > 0,
+			"sharded thread counter needs at least one shard"
+		);
+		Self {
+			shards: (0..shards)
+				.map(|_| Shard {
+					taken: AtomicU64::new(0),
+					dropped: AtomicU64::new(0),
+				})
+				.collect::<Vec<_>>()
+				.into_boxed_slice(),
+			waiters: AtomicUsize::new(0),
+			lock: Mutex::new(()),
+			condvar: Condvar::new(),
+		}
+	}
+
+	/// Takes a new [`ShardedTicket`] from the current thread's shard, which
+	/// decrements the count again when dropped.
+	#[must_use = "dropping the ShardedTicket immediately decrements the counter"]
+	pub fn ticket(&self) -> ShardedTicket<'_> {
+		self.shard().taken.fetch_add(1, Ordering::SeqCst);
+		ShardedTicket { counter: self }
+	}
+
+	/// Returns the number of tickets currently alive.
+	///
+	/// # Note
+	/// This visits every shard, so it's slower than
+	/// [`RawThreadCounter::count()`](crate::RawThreadCounter::count()). Just
+	/// like that, the returned value is only a snapshot, and may already be
+	/// stale by the time it is returned.
+	pub fn count(&self) -> usize {
+		// Read every running total of dropped tickets before any total of
+		// taken ones. As they only ever go up, the dropped total is then at
+		// most what it was between the two passes, and the taken total at
+		// least, so a count of zero means it really was zero at that point.
+		let dropped: u64 = self
+			.shards
+			.iter()
+			.map(|shard| shard.dropped.load(Ordering::SeqCst))
+			.sum();
+		let taken: u64 = self
+			.shards
+			.iter()
+			.map(|shard| shard.taken.load(Ordering::SeqCst))
+			.sum();
+		usize::try_from(taken.saturating_sub(dropped)).unwrap_or(usize::MAX)
+	}
+
+	/// Returns `true` if no tickets are currently alive.
+	///
+	/// If this returns `true`, there really was a moment with no tickets
+	/// alive, even though the shards aren't all read at once.
+	pub fn is_empty(&self) -> bool {
+		self.count() == 0
+	}
+
+	/// Returns the number of shards the count is spread across.
+	pub fn shards(&self) -> usize {
+		self.shards.len()
+	}
+
+	/// Waits for the count to reach zero, with an optional timeout.
+	///
+	/// # Arguments
+	/// * `timeout` - An optional duration to wait. If `None`, waits
+	///   indefinitely.
+	///
+	/// # Returns
+	/// * [`WaitResult::Completed`] if the count reached zero.
+	/// * [`WaitResult::TimedOut`] if the timeout was reached before the count
+	///   reached zero.
+	pub fn wait(&self, timeout: impl Into<Option<Duration>>) -> WaitResult {
+		let deadline = deadline_after(timeout.into());
+		if self.is_empty() {
+			return WaitResult::Completed;
+		}
+		if has_passed(deadline) {
+			return WaitResult::TimedOut;
+		}
+		let _waiter = WaiterGuard::new(&self.waiters);
+		let mut guard = self.lock.lock();
+		loop {
+			if self.is_empty() {
+				return WaitResult::Completed;
+			}
+			match deadline {
+				Some(deadline) => {
+					if self.condvar.wait_until(&mut guard, deadline) {
+						// The count may have reached zero right as we timed out.
+						return match self.is_empty() {
+							true => WaitResult::Completed,
+							false => WaitResult::TimedOut,
+						};
+					}
+				}
+				None => self.condvar.wait(&mut guard),
+			}
+		}
+	}
+
+	/// Returns the current thread's shard.
+	fn shard(&self) -> &Shard {
+		std::thread_local! {
+			static THREAD_INDEX: usize = {
+				static NEXT_INDEX: core::sync::atomic::AtomicUsize =
+					core::sync::atomic::AtomicUsize::new(0);
+				NEXT_INDEX.fetch_add(1, core::sync::atomic::Ordering::Relaxed)
+			};
+		}
+		let index = THREAD_INDEX.with(|index| *index);
+		&self.shards[index % self.shards.len()]
+	}
+}
+
+impl Default for ShardedThreadCounter {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl fmt::Debug for ShardedThreadCounter {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ShardedThreadCounter")
+			.field("count", &self.count())
+			.field("shards", &self.shards())
+			.finish_non_exhaustive()
+	}
+}
+
+/// A RAII guard for a [`ShardedThreadCounter`], created by
+/// [`ShardedThreadCounter::ticket()`].
+///
+/// This decrements the counter when dropped, just like a regular
+/// [`Ticket`](crate::Ticket).
+#[must_use = "dropping the ShardedTicket immediately decrements the counter"]
+pub struct ShardedTicket<'a> {
+	counter: &'a ShardedThreadCounter,
+}
+
+impl Drop for ShardedTicket<'_> {
+	fn drop(&mut self) {
+		// Count the drop on the current thread's shard, which isn't
+		// necessarily the one the ticket was taken from, as that's the one
+		// this thread is least likely to contend on.
+		self.counter.shard().dropped.fetch_add(1, Ordering::SeqCst);
+		atomic::store_load_fence();
+		if self.counter.waiters.load(Ordering::SeqCst) > 0 {
+			// Waiting threads hold the lock from checking the count until
+			// they're parked, so taking it here ensures none of them can miss
+			// this notification.
+			drop(self.counter.lock.lock());
+			self.counter.condvar.notify_all();
+		}
+	}
+}
+
+impl fmt::Debug for ShardedTicket<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ShardedTicket").finish_non_exhaustive()
+	}
+}
@@ -0,0 +1,84 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+use crate::{RawThreadCounter, ThreadCounter};
+
+/// A point-in-time snapshot of a thread counter's state.
+///
+/// Unlike the live counter, this is a plain value, making it suitable for
+/// logging, or for exposing over something like an admin HTTP endpoint. With
+/// the `serde` feature, it can be serialized and deserialized, and so can the
+/// counters themselves, by way of this snapshot.
+///
+/// ```rust
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use thread_counter::ThreadCounter;
+///
+/// let counter = ThreadCounter::default();
+/// let _ticket = counter.ticket().unwrap();
+/// assert_eq!(serde_json::to_string(&counter).unwrap(), r#"{"count":1}"#);
+///
+/// let restored: ThreadCounter = serde_json::from_str(r#"{"count":3}"#).unwrap();
+/// assert_eq!(restored.count(), 3);
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CounterSnapshot {
+	/// The number of active threads or operations at the time of the snapshot.
+	pub count: usize,
+}
+
+impl RawThreadCounter {
+	/// Takes a snapshot of the thread counter's current state.
+	///
+	/// # Note
+	/// Just like [`RawThreadCounter::count()`], the snapshot may already be
+	/// stale by the time it is returned.
+	pub fn snapshot(&self) -> CounterSnapshot {
+		CounterSnapshot {
+			count: self.count(),
+		}
+	}
+}
+
+/// Creates a fresh counter, starting at the snapshot's count.
+impl From<CounterSnapshot> for RawThreadCounter {
+	fn from(snapshot: CounterSnapshot) -> Self {
+		Self::with_count(snapshot.count)
+	}
+}
+
+/// Creates a fresh counter, starting at the snapshot's count.
+impl From<CounterSnapshot> for ThreadCounter {
+	fn from(snapshot: CounterSnapshot) -> Self {
+		Self::with_count(snapshot.count)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for RawThreadCounter {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		self.snapshot().serialize(serializer)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ThreadCounter {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		self.inner.serialize(serializer)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RawThreadCounter {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		CounterSnapshot::deserialize(deserializer).map(Self::from)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ThreadCounter {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		CounterSnapshot::deserialize(deserializer).map(Self::from)
+	}
+}
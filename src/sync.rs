@@ -0,0 +1,314 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! The synchronization primitives backing the thread counter.
+//!
+//! With the `parking_lot` feature, these are built on `parking_lot`. With
+//! just the `std` feature, they're built on the standard library's instead.
+//! Without either, they're spin-based. When built with `--cfg loom`, they're
+//! loom's instead, so that the crate can be model-checked. Either way, they
+//! expose just the small API that the rest of the crate needs.
+
+#[cfg(loom)]
+pub(crate) use loom_backend::{Condvar, Mutex, MutexGuard};
+#[cfg(all(feature = "parking_lot", not(loom)))]
+pub(crate) use parking_lot_backend::{Condvar, Mutex, MutexGuard};
+#[cfg(not(feature = "std"))]
+pub(crate) use spin_backend::{Condvar, Instant, Mutex, MutexGuard};
+#[cfg(feature = "std")]
+pub(crate) use std::time::Instant;
+#[cfg(all(feature = "std", not(feature = "parking_lot"), not(loom)))]
+pub(crate) use std_backend::{Condvar, Mutex, MutexGuard};
+
+/// The atomic integer types the counter is built on.
+pub(crate) mod atomic {
+	pub(crate) use core::sync::atomic::Ordering;
+	#[cfg(not(loom))]
+	pub(crate) use core::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize};
+	#[cfg(loom)]
+	pub(crate) use loom::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize};
+
+	/// Orders a `SeqCst` write before a `SeqCst` load of a different atomic,
+	/// as needed when a waiter and a notifier each write their own flag and
+	/// then check the other's.
+	///
+	/// `SeqCst` operations already guarantee this on their own, so this is a
+	/// no-op, except under loom, which models them as `AcqRel` and would
+	/// otherwise report lost wakeups that can't actually happen.
+	#[inline(always)]
+	pub(crate) fn store_load_fence() {
+		#[cfg(loom)]
+		loom::sync::atomic::fence(Ordering::SeqCst);
+	}
+
+	/// Updates an atomic through exclusive access, without any atomic
+	/// operations, returning its previous value.
+	#[cfg(not(loom))]
+	#[inline(always)]
+	pub(crate) fn update_mut(atomic: &mut AtomicUsize, f: impl FnOnce(usize) -> usize) -> usize {
+		let value = atomic.get_mut();
+		core::mem::replace(value, f(*value))
+	}
+
+	/// Updates an atomic through exclusive access, without any atomic
+	/// operations, returning its previous value.
+	#[cfg(loom)]
+	pub(crate) fn update_mut(atomic: &mut AtomicUsize, f: impl FnOnce(usize) -> usize) -> usize {
+		atomic.with_mut(|value| core::mem::replace(value, f(*value)))
+	}
+}
+
+#[cfg(all(feature = "parking_lot", not(loom)))]
+mod parking_lot_backend {
+	use super::Instant;
+	pub(crate) use parking_lot::{Mutex, MutexGuard};
+
+	/// A condition variable, parking waiting threads until notified.
+	pub(crate) struct Condvar(parking_lot::Condvar);
+
+	impl Condvar {
+		pub(crate) const fn new() -> Self {
+			Self(parking_lot::Condvar::new())
+		}
+
+		pub(crate) fn notify_all(&self) {
+			self.0.notify_all();
+		}
+
+		pub(crate) fn wait<T>(&self, mutex_guard: &mut MutexGuard<'_, T>) {
+			self.0.wait(mutex_guard);
+		}
+
+		/// Returns whether the deadline passed.
+		pub(crate) fn wait_until<T>(
+			&self,
+			mutex_guard: &mut MutexGuard<'_, T>,
+			deadline: Instant,
+		) -> bool {
+			self.0.wait_until(mutex_guard, deadline).timed_out()
+		}
+	}
+}
+
+#[cfg(all(feature = "std", not(feature = "parking_lot"), not(loom)))]
+mod std_backend {
+	use super::Instant;
+	use core::ops::{Deref, DerefMut};
+	use std::sync::PoisonError;
+
+	/// A mutex from the standard library.
+	///
+	/// The data behind these locks is never left half-updated, so poisoning
+	/// is ignored.
+	pub(crate) struct Mutex<T>(std::sync::Mutex<T>);
+
+	impl<T> Mutex<T> {
+		pub(crate) const fn new(value: T) -> Self {
+			Self(std::sync::Mutex::new(value))
+		}
+
+		pub(crate) fn lock(&self) -> MutexGuard<'_, T> {
+			MutexGuard(Some(self.0.lock().unwrap_or_else(PoisonError::into_inner)))
+		}
+	}
+
+	/// A guard for [`Mutex`].
+	pub(crate) struct MutexGuard<'a, T>(
+		/// This is only ever `None` while [`Condvar`] is waiting.
+		Option<std::sync::MutexGuard<'a, T>>,
+	);
+
+	impl<T> Deref for MutexGuard<'_, T> {
+		type Target = T;
+
+		fn deref(&self) -> &T {
+			self.0.as_ref().expect("mutex guard is held")
+		}
+	}
+
+	impl<T> DerefMut for MutexGuard<'_, T> {
+		fn deref_mut(&mut self) -> &mut T {
+			self.0.as_mut().expect("mutex guard is held")
+		}
+	}
+
+	/// A condition variable from the standard library.
+	pub(crate) struct Condvar(std::sync::Condvar);
+
+	impl Condvar {
+		pub(crate) const fn new() -> Self {
+			Self(std::sync::Condvar::new())
+		}
+
+		pub(crate) fn notify_all(&self) {
+			self.0.notify_all();
+		}
+
+		pub(crate) fn wait<T>(&self, mutex_guard: &mut MutexGuard<'_, T>) {
+			let guard = mutex_guard.0.take().expect("mutex guard is held");
+			mutex_guard.0 = Some(self.0.wait(guard).unwrap_or_else(PoisonError::into_inner));
+		}
+
+		/// Returns whether the deadline passed.
+		pub(crate) fn wait_until<T>(
+			&self,
+			mutex_guard: &mut MutexGuard<'_, T>,
+			deadline: Instant,
+		) -> bool {
+			let guard = mutex_guard.0.take().expect("mutex guard is held");
+			let timeout = deadline.saturating_duration_since(Instant::now());
+			let (guard, result) = self
+				.0
+				.wait_timeout(guard, timeout)
+				.unwrap_or_else(PoisonError::into_inner);
+			mutex_guard.0 = Some(guard);
+			result.timed_out()
+		}
+	}
+}
+
+#[cfg(not(feature = "std"))]
+mod spin_backend {
+	use core::ops::{Deref, DerefMut};
+
+	/// A spin-based mutex.
+	pub(crate) struct Mutex<T>(spin::Mutex<T>);
+
+	impl<T> Mutex<T> {
+		pub(crate) const fn new(value: T) -> Self {
+			Self(spin::Mutex::new(value))
+		}
+
+		pub(crate) fn lock(&self) -> MutexGuard<'_, T> {
+			MutexGuard {
+				mutex: self,
+				guard: Some(self.0.lock()),
+			}
+		}
+	}
+
+	/// A guard for [`Mutex`], which remembers which mutex it came from, so
+	/// that [`Condvar`] can temporarily release it.
+	pub(crate) struct MutexGuard<'a, T> {
+		mutex: &'a Mutex<T>,
+		/// This is only ever `None` while [`Condvar`] is waiting.
+		guard: Option<spin::MutexGuard<'a, T>>,
+	}
+
+	impl<T> Deref for MutexGuard<'_, T> {
+		type Target = T;
+
+		fn deref(&self) -> &T {
+			self.guard.as_ref().expect("mutex guard is held")
+		}
+	}
+
+	impl<T> DerefMut for MutexGuard<'_, T> {
+		fn deref_mut(&mut self) -> &mut T {
+			self.guard.as_mut().expect("mutex guard is held")
+		}
+	}
+
+	/// A condition variable that busy-waits, as there's nothing to park
+	/// threads on without `std`.
+	///
+	/// As waiting threads return after a moment without needing to be woken,
+	/// notifying them is a no-op, and callers are expected to re-check their
+	/// condition in a loop.
+	pub(crate) struct Condvar;
+
+	impl Condvar {
+		pub(crate) const fn new() -> Self {
+			Self
+		}
+
+		pub(crate) fn notify_all(&self) {}
+
+		pub(crate) fn wait<T>(&self, mutex_guard: &mut MutexGuard<'_, T>) {
+			// Let go of the lock for a moment, so other threads can make
+			// progress.
+			drop(mutex_guard.guard.take());
+			core::hint::spin_loop();
+			mutex_guard.guard = Some(mutex_guard.mutex.0.lock());
+		}
+
+		pub(crate) fn wait_until<T>(
+			&self,
+			_mutex_guard: &mut MutexGuard<'_, T>,
+			deadline: Instant,
+		) -> bool {
+			match deadline {}
+		}
+	}
+
+	/// There's no clock to measure timeouts with without `std`, so deadlines
+	/// can never be constructed.
+	#[derive(Clone, Copy)]
+	pub(crate) enum Instant {}
+}
+
+#[cfg(loom)]
+mod loom_backend {
+	use super::Instant;
+	use core::ops::{Deref, DerefMut};
+
+	/// A mutex that loom can model.
+	pub(crate) struct Mutex<T>(loom::sync::Mutex<T>);
+
+	impl<T> Mutex<T> {
+		pub(crate) fn new(value: T) -> Self {
+			Self(loom::sync::Mutex::new(value))
+		}
+
+		pub(crate) fn lock(&self) -> MutexGuard<'_, T> {
+			MutexGuard(Some(self.0.lock().expect("mutex isn't poisoned")))
+		}
+	}
+
+	/// A guard for [`Mutex`].
+	pub(crate) struct MutexGuard<'a, T>(
+		/// This is only ever `None` while [`Condvar`] is waiting.
+		Option<loom::sync::MutexGuard<'a, T>>,
+	);
+
+	impl<T> Deref for MutexGuard<'_, T> {
+		type Target = T;
+
+		fn deref(&self) -> &T {
+			self.0.as_ref().expect("mutex guard is held")
+		}
+	}
+
+	impl<T> DerefMut for MutexGuard<'_, T> {
+		fn deref_mut(&mut self) -> &mut T {
+			self.0.as_mut().expect("mutex guard is held")
+		}
+	}
+
+	/// A condition variable that loom can model.
+	pub(crate) struct Condvar(loom::sync::Condvar);
+
+	impl Condvar {
+		pub(crate) fn new() -> Self {
+			Self(loom::sync::Condvar::new())
+		}
+
+		pub(crate) fn notify_all(&self) {
+			self.0.notify_all();
+		}
+
+		pub(crate) fn wait<T>(&self, mutex_guard: &mut MutexGuard<'_, T>) {
+			let guard = mutex_guard.0.take().expect("mutex guard is held");
+			mutex_guard.0 = Some(self.0.wait(guard).expect("mutex isn't poisoned"));
+		}
+
+		/// Loom doesn't model time, so this waits until notified, and never
+		/// reports the deadline as passed.
+		pub(crate) fn wait_until<T>(
+			&self,
+			mutex_guard: &mut MutexGuard<'_, T>,
+			_deadline: Instant,
+		) -> bool {
+			self.wait(mutex_guard);
+			false
+		}
+	}
+}
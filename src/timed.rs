@@ -0,0 +1,112 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+use crate::{sync::Instant, Closed, ThreadCounter, Ticket};
+use core::{fmt, time::Duration};
+
+/// A [`Ticket`] that keeps track of how long it's been held, created by
+/// [`ThreadCounter::ticket_timed()`].
+///
+/// If it's held for longer than it was meant to be, dropping it runs the
+/// counter's [`ThreadCounterBuilder::on_slow_ticket()`](crate::ThreadCounterBuilder::on_slow_ticket())
+/// callback, if any, with how long it was held for. With the `tracing`
+/// feature, it also emits a warning. Otherwise, it decrements the counter
+/// when dropped, just like a regular [`Ticket`].
+#[must_use = "dropping the TimedTicket immediately decrements the counter"]
+pub struct TimedTicket {
+	ticket: Ticket,
+	/// When the ticket was taken.
+	started: Instant,
+	/// How long the ticket can be held for before it's considered slow.
+	warn_after: Duration,
+}
+
+impl TimedTicket {
+	/// Returns the underlying [`Ticket`].
+	pub fn ticket(&self) -> &Ticket {
+		&self.ticket
+	}
+
+	/// Returns how long this ticket has been held for so far.
+	pub fn elapsed(&self) -> Duration {
+		self.started.elapsed()
+	}
+}
+
+impl Drop for TimedTicket {
+	fn drop(&mut self) {
+		let elapsed = self.elapsed();
+		if elapsed <= self.warn_after {
+			return;
+		}
+		#[cfg(feature = "tracing")]
+		tracing::warn!(
+			?elapsed,
+			warn_after = ?self.warn_after,
+			"thread counter ticket held for too long"
+		);
+		if let Some(callback) = &self.ticket.counter.slow_callback {
+			callback(elapsed);
+		}
+	}
+}
+
+impl fmt::Debug for TimedTicket {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("TimedTicket")
+			.field("elapsed", &self.elapsed())
+			.field("warn_after", &self.warn_after)
+			.finish_non_exhaustive()
+	}
+}
+
+impl ThreadCounter {
+	/// Creates a new [`TimedTicket`] from this thread counter, which reports
+	/// it if it's held for longer than `warn_after`.
+	///
+	/// This behaves just like [`ThreadCounter::ticket()`], but surfaces slow
+	/// operations without needing to time every code path by hand. Register a
+	/// callback with
+	/// [`ThreadCounterBuilder::on_slow_ticket()`](crate::ThreadCounterBuilder::on_slow_ticket())
+	/// to find out about them.
+	///
+	/// # Arguments
+	/// * `warn_after` - How long the ticket can be held for before it's
+	///   reported as slow when dropped.
+	///
+	/// # Returns
+	/// * `Ok` with a new [`TimedTicket`] instance associated with this counter.
+	/// * `Err` if the counter has been closed with
+	///   [`RawThreadCounter::close()`](crate::RawThreadCounter::close()).
+	///
+	/// ```rust
+	/// use std::{
+	/// 	sync::{Arc, Mutex},
+	/// 	thread,
+	/// 	time::Duration,
+	/// };
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let slow = Arc::new(Mutex::new(Vec::new()));
+	/// let counter = ThreadCounter::builder()
+	/// 	.on_slow_ticket({
+	/// 		let slow = slow.clone();
+	/// 		move |elapsed| slow.lock().unwrap().push(elapsed)
+	/// 	})
+	/// 	.build();
+	///
+	/// let ticket = counter.ticket_timed(Duration::from_millis(10)).unwrap();
+	/// thread::sleep(Duration::from_millis(20));
+	/// drop(ticket);
+	///
+	/// let slow = slow.lock().unwrap();
+	/// assert_eq!(slow.len(), 1);
+	/// assert!(slow[0] >= Duration::from_millis(20));
+	/// ```
+	#[must_use = "dropping the TimedTicket immediately decrements the counter"]
+	pub fn ticket_timed(&self, warn_after: Duration) -> Result<TimedTicket, Closed> {
+		Ok(TimedTicket {
+			ticket: self.ticket()?,
+			started: Instant::now(),
+			warn_after,
+		})
+	}
+}
@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+use crate::{ThreadCounter, Ticket};
+
+/// An iterator that pairs each item with a fresh [`Ticket`], created by
+/// [`ThreadCounter::track()`].
+#[derive(Debug)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Track<I: Iterator> {
+	counter: ThreadCounter,
+	iter: I,
+	/// An item that was taken from `iter`, but couldn't get a ticket, as the
+	/// counter was closed.
+	pending: Option<I::Item>,
+}
+
+impl ThreadCounter {
+	/// Wraps an iterator, so that each item it yields comes with a fresh
+	/// [`Ticket`] from this counter.
+	///
+	/// This saves taking a ticket at the top of every loop body, and makes the
+	/// intent explicit: hold onto each ticket while processing its item, and
+	/// drop it once done.
+	///
+	/// Tickets are taken as items are yielded, so if the counter has a
+	/// capacity, the iterator blocks until there's room, naturally applying
+	/// backpressure. Once the counter is closed, the iterator ends.
+	///
+	/// ```rust
+	/// use std::thread;
+	/// use thread_counter::ThreadCounter;
+	///
+	/// let counter = ThreadCounter::default();
+	/// for (ticket, item) in counter.track(0..5) {
+	/// 	thread::spawn(move || {
+	/// 		// Process the item, holding onto the ticket.
+	/// 		let _ = item * 2;
+	/// 		drop(ticket);
+	/// 	});
+	/// }
+	/// counter.wait_indefinitely();
+	/// ```
+	pub fn track<I: IntoIterator>(&self, iter: I) -> Track<I::IntoIter> {
+		Track {
+			counter: self.clone(),
+			iter: iter.into_iter(),
+			pending: None,
+		}
+	}
+}
+
+impl<I: Iterator> Iterator for Track<I> {
+	type Item = (Ticket, I::Item);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		// Take the item first, so that the counter isn't touched once the
+		// inner iterator is exhausted.
+		let item = self.pending.take().or_else(|| self.iter.next())?;
+		match self.counter.ticket() {
+			Ok(ticket) => Some((ticket, item)),
+			Err(_) => {
+				self.pending = Some(item);
+				None
+			}
+		}
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		// The counter may be closed at any point.
+		let pending = usize::from(self.pending.is_some());
+		(
+			0,
+			self.iter
+				.size_hint()
+				.1
+				.and_then(|upper| upper.checked_add(pending)),
+		)
+	}
+}
@@ -0,0 +1,252 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+#[cfg(feature = "std")]
+use crate::{sync::Instant, WaitBuilder};
+use crate::{CounterSnapshot, RawThreadCounter, ThreadCounter, WaitResult};
+use alloc::sync::Arc;
+#[cfg(feature = "async")]
+use core::task::{Context, Poll};
+#[cfg(feature = "std")]
+use core::time::Duration;
+use core::{fmt, num::NonZeroUsize};
+
+/// A read-only handle to a [`ThreadCounter`], created by
+/// [`ThreadCounter::view()`].
+///
+/// This can read and wait on the counter, but not change it: there's no way
+/// to take tickets, or to increment, decrement or reset the count. Handing
+/// this to observers instead of the counter itself makes sure at the type
+/// level that they can't accidentally throw off the count.
+///
+/// Just like [`ThreadCounter`], cloning a view is cheap, and it keeps the
+/// underlying counter alive.
+///
+/// ```rust
+/// use thread_counter::ThreadCounter;
+///
+/// let counter = ThreadCounter::default();
+/// let view = counter.view();
+///
+/// let ticket = counter.ticket().unwrap();
+/// assert_eq!(view.count(), 1);
+/// drop(ticket);
+/// assert!(view.is_empty());
+/// ```
+#[derive(Clone)]
+pub struct CounterView {
+	inner: Arc<RawThreadCounter>,
+}
+
+impl ThreadCounter {
+	/// Creates a read-only [`CounterView`] of this counter.
+	pub fn view(&self) -> CounterView {
+		CounterView {
+			inner: Arc::clone(&self.inner),
+		}
+	}
+}
+
+impl CounterView {
+	/// See [`RawThreadCounter::count()`].
+	pub fn count(&self) -> usize {
+		self.inner.count()
+	}
+
+	/// See [`RawThreadCounter::count_approx()`].
+	pub fn count_approx(&self) -> usize {
+		self.inner.count_approx()
+	}
+
+	/// See [`RawThreadCounter::count_nonzero()`].
+	pub fn count_nonzero(&self) -> Option<NonZeroUsize> {
+		self.inner.count_nonzero()
+	}
+
+	/// See [`RawThreadCounter::is_empty()`].
+	pub fn is_empty(&self) -> bool {
+		self.inner.is_empty()
+	}
+
+	/// See [`RawThreadCounter::has_count()`].
+	pub fn has_count(&self, n: usize) -> bool {
+		self.inner.has_count(n)
+	}
+
+	/// See [`RawThreadCounter::peak()`].
+	pub fn peak(&self) -> usize {
+		self.inner.peak()
+	}
+
+	/// See [`RawThreadCounter::generation()`].
+	pub fn generation(&self) -> u64 {
+		self.inner.generation()
+	}
+
+	/// See [`RawThreadCounter::idle_for()`].
+	#[cfg(feature = "std")]
+	pub fn idle_for(&self) -> Option<Duration> {
+		self.inner.idle_for()
+	}
+
+	/// See [`RawThreadCounter::capacity()`].
+	pub fn capacity(&self) -> Option<usize> {
+		self.inner.capacity()
+	}
+
+	/// See [`RawThreadCounter::at_capacity()`].
+	pub fn at_capacity(&self) -> bool {
+		self.inner.at_capacity()
+	}
+
+	/// See [`RawThreadCounter::is_closed()`].
+	pub fn is_closed(&self) -> bool {
+		self.inner.is_closed()
+	}
+
+	/// See [`RawThreadCounter::waiters()`].
+	pub fn waiters(&self) -> usize {
+		self.inner.waiters()
+	}
+
+	/// See [`RawThreadCounter::snapshot()`].
+	pub fn snapshot(&self) -> CounterSnapshot {
+		self.inner.snapshot()
+	}
+
+	/// See [`RawThreadCounter::try_wait()`].
+	pub fn try_wait(&self) -> bool {
+		self.inner.try_wait()
+	}
+
+	/// See [`RawThreadCounter::wait()`].
+	#[cfg(feature = "std")]
+	pub fn wait(&self, timeout: impl Into<Option<Duration>>) -> WaitResult {
+		self.inner.wait(timeout)
+	}
+
+	/// See [`RawThreadCounter::wait_indefinitely()`].
+	pub fn wait_indefinitely(&self) -> WaitResult {
+		self.inner.wait_indefinitely()
+	}
+
+	/// See [`RawThreadCounter::wait_deadline()`].
+	#[cfg(feature = "std")]
+	pub fn wait_deadline(&self, deadline: Instant) -> WaitResult {
+		self.inner.wait_deadline(deadline)
+	}
+
+	/// See [`RawThreadCounter::wait_until()`].
+	#[cfg(feature = "std")]
+	pub fn wait_until(&self, deadline: impl Into<Option<Instant>>) -> WaitResult {
+		self.inner.wait_until(deadline)
+	}
+
+	/// See [`RawThreadCounter::wait_for()`].
+	#[cfg(feature = "std")]
+	pub fn wait_for(&self, target: usize, timeout: impl Into<Option<Duration>>) -> WaitResult {
+		self.inner.wait_for(target, timeout)
+	}
+
+	/// See [`RawThreadCounter::wait_while()`].
+	#[cfg(feature = "std")]
+	pub fn wait_while(
+		&self,
+		condition: impl FnMut(usize) -> bool,
+		timeout: impl Into<Option<Duration>>,
+	) -> WaitResult {
+		self.inner.wait_while(condition, timeout)
+	}
+
+	/// See [`RawThreadCounter::wait_nonzero()`].
+	#[cfg(feature = "std")]
+	pub fn wait_nonzero(&self, timeout: impl Into<Option<Duration>>) -> WaitResult {
+		self.inner.wait_nonzero(timeout)
+	}
+
+	/// See [`RawThreadCounter::wait_change()`].
+	#[cfg(feature = "std")]
+	pub fn wait_change(&self, from: usize, timeout: impl Into<Option<Duration>>) -> WaitResult {
+		self.inner.wait_change(from, timeout)
+	}
+
+	/// See [`RawThreadCounter::wait_for_generation()`].
+	#[cfg(feature = "std")]
+	pub fn wait_for_generation(
+		&self,
+		generation: u64,
+		timeout: impl Into<Option<Duration>>,
+	) -> WaitResult {
+		self.inner.wait_for_generation(generation, timeout)
+	}
+
+	/// See [`RawThreadCounter::wait_for_at_least()`].
+	#[cfg(feature = "std")]
+	pub fn wait_for_at_least(&self, n: usize, timeout: impl Into<Option<Duration>>) -> WaitResult {
+		self.inner.wait_for_at_least(n, timeout)
+	}
+
+	/// See [`RawThreadCounter::wait_quiescent()`].
+	#[cfg(feature = "std")]
+	pub fn wait_quiescent(
+		&self,
+		quiet: Duration,
+		timeout: impl Into<Option<Duration>>,
+	) -> WaitResult {
+		self.inner.wait_quiescent(quiet, timeout)
+	}
+
+	/// See [`RawThreadCounter::wait_with_progress()`].
+	#[cfg(feature = "std")]
+	pub fn wait_with_progress(
+		&self,
+		interval: Duration,
+		progress: impl FnMut(usize),
+		timeout: impl Into<Option<Duration>>,
+	) -> WaitResult {
+		self.inner.wait_with_progress(interval, progress, timeout)
+	}
+
+	/// See [`RawThreadCounter::wait_remaining()`].
+	#[cfg(feature = "std")]
+	pub fn wait_remaining(&self, timeout: impl Into<Option<Duration>>) -> usize {
+		self.inner.wait_remaining(timeout)
+	}
+
+	/// See [`RawThreadCounter::wait_builder()`].
+	#[cfg(feature = "std")]
+	pub fn wait_builder(&self) -> WaitBuilder<'_> {
+		self.inner.wait_builder()
+	}
+
+	/// See [`RawThreadCounter::wait_async()`].
+	#[cfg(feature = "async")]
+	pub async fn wait_async(&self) {
+		self.inner.wait_async().await
+	}
+
+	/// See [`RawThreadCounter::poll_wait()`].
+	#[cfg(feature = "async")]
+	pub fn poll_wait(&self, cx: &mut Context<'_>) -> Poll<()> {
+		self.inner.poll_wait(cx)
+	}
+
+	/// See [`RawThreadCounter::wait_async_timeout()`].
+	#[cfg(feature = "async")]
+	pub async fn wait_async_timeout(&self, timeout: Duration) -> WaitResult {
+		self.inner.wait_async_timeout(timeout).await
+	}
+
+	/// See [`RawThreadCounter::wait_async_cancellable()`].
+	#[cfg(feature = "tokio-util")]
+	pub async fn wait_async_cancellable(
+		&self,
+		token: &tokio_util::sync::CancellationToken,
+	) -> WaitResult {
+		self.inner.wait_async_cancellable(token).await
+	}
+}
+
+impl fmt::Debug for CounterView {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.inner.debug_fields(&mut f.debug_struct("CounterView"))
+	}
+}
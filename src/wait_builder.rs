@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+use crate::{deadline_after, RawThreadCounter, WaitResult};
+use alloc::boxed::Box;
+use core::{fmt, time::Duration};
+
+/// A builder for combining wait options, created by
+/// [`RawThreadCounter::wait_builder()`].
+///
+/// This covers the same ground as the various `wait_*` methods, but lets
+/// their options be mixed freely, rather than needing a separate method for
+/// every combination of them. By default, it waits indefinitely for the count
+/// to reach zero.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use thread_counter::ThreadCounter;
+///
+/// let counter = ThreadCounter::with_count(6);
+/// let mut reports = Vec::new();
+/// let result = counter
+/// 	.wait_builder()
+/// 	.target(4)
+/// 	.timeout(Duration::from_millis(35))
+/// 	.with_progress(Duration::from_millis(10), |remaining| {
+/// 		reports.push(remaining)
+/// 	})
+/// 	.run();
+/// assert!(result.is_timed_out());
+/// assert!(reports.iter().all(|&remaining| remaining == 6));
+/// ```
+#[must_use = "a wait builder does nothing until `run()` is called"]
+pub struct WaitBuilder<'a> {
+	counter: &'a RawThreadCounter,
+	target: usize,
+	timeout: Option<Duration>,
+	progress: Option<Progress<'a>>,
+}
+
+/// A progress callback, along with how often to call it.
+type Progress<'a> = (Duration, Box<dyn FnMut(usize) + 'a>);
+
+impl<'a> WaitBuilder<'a> {
+	/// Waits for the count to drop to `target` or below, rather than to zero.
+	///
+	/// See [`RawThreadCounter::wait_for()`].
+	pub fn target(mut self, target: usize) -> Self {
+		self.target = target;
+		self
+	}
+
+	/// Gives up waiting once `timeout` has passed. If `None`, waits
+	/// indefinitely.
+	pub fn timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+		self.timeout = timeout.into();
+		self
+	}
+
+	/// Calls `progress` with the current count every `interval`, until
+	/// waiting ends.
+	///
+	/// See [`RawThreadCounter::wait_with_progress()`].
+	///
+	/// # Panics
+	/// Panics if `interval` is zero.
+	pub fn with_progress(mut self, interval: Duration, progress: impl FnMut(usize) + 'a) -> Self {
+		assert!(!interval.is_zero(), "progress interval must be nonzero");
+		self.progress = Some((interval, Box::new(progress)));
+		self
+	}
+
+	/// Waits with the configured options.
+	///
+	/// # Returns
+	/// * [`WaitResult::Completed`] if the count dropped to the target or below.
+	/// * [`WaitResult::TimedOut`] if the timeout was reached first.
+	/// * [`WaitResult::Interrupted`] if waiting was cut short by
+	///   [`RawThreadCounter::notify_all()`].
+	pub fn run(self) -> WaitResult {
+		let target = self.target;
+		let condition = |count| count > target;
+		let deadline = deadline_after(self.timeout);
+		match self.progress {
+			Some((interval, mut progress)) => {
+				self.counter
+					.block_with_progress(condition, deadline, interval, &mut progress)
+			}
+			None => self.counter.block_while(condition, deadline).0,
+		}
+	}
+}
+
+impl fmt::Debug for WaitBuilder<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("WaitBuilder")
+			.field("target", &self.target)
+			.field("timeout", &self.timeout)
+			.field(
+				"progress_interval",
+				&self.progress.as_ref().map(|(interval, _)| interval),
+			)
+			.finish_non_exhaustive()
+	}
+}
+
+impl RawThreadCounter {
+	/// Returns a [`WaitBuilder`], for combining wait options.
+	pub fn wait_builder(&self) -> WaitBuilder<'_> {
+		WaitBuilder {
+			counter: self,
+			target: 0,
+			timeout: None,
+			progress: None,
+		}
+	}
+}
@@ -0,0 +1,67 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+use crate::{ThreadCounter, WaitResult};
+
+/// A [`ThreadCounter`] with the API of Go's `sync.WaitGroup`, or crossbeam's
+/// `WaitGroup`.
+///
+/// This is just a thin wrapper for those already familiar with that API:
+/// [`WaitGroup::add()`] and [`WaitGroup::done()`] increment and decrement
+/// the count, and [`WaitGroup::wait()`] blocks until it reaches zero. Just
+/// like [`ThreadCounter`], cloning a wait group gives another handle to the
+/// same count.
+///
+/// ```rust
+/// use std::thread;
+/// use thread_counter::WaitGroup;
+///
+/// let wg = WaitGroup::default();
+/// for _ in 0..4 {
+/// 	wg.add(1);
+/// 	let wg = wg.clone();
+/// 	thread::spawn(move || {
+/// 		// Do some work...
+/// 		wg.done();
+/// 	});
+/// }
+/// wg.wait();
+/// assert_eq!(wg.counter().count(), 0);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WaitGroup {
+	counter: ThreadCounter,
+}
+
+impl WaitGroup {
+	/// Adds `n` to the count of operations to wait for.
+	///
+	/// See [`RawThreadCounter::increment_by()`](crate::RawThreadCounter::increment_by()).
+	pub fn add(&self, n: usize) {
+		self.counter.increment_by(n);
+	}
+
+	/// Marks one operation as done.
+	///
+	/// See [`RawThreadCounter::decrement()`](crate::RawThreadCounter::decrement()).
+	pub fn done(&self) {
+		self.counter.decrement();
+	}
+
+	/// Blocks until every operation has been marked as done.
+	///
+	/// See [`RawThreadCounter::wait_indefinitely()`](crate::RawThreadCounter::wait_indefinitely()).
+	pub fn wait(&self) -> WaitResult {
+		self.counter.wait_indefinitely()
+	}
+
+	/// Returns the underlying [`ThreadCounter`], for everything else it can
+	/// do, such as waiting with a timeout.
+	pub fn counter(&self) -> &ThreadCounter {
+		&self.counter
+	}
+}
+
+impl From<ThreadCounter> for WaitGroup {
+	fn from(counter: ThreadCounter) -> Self {
+		Self { counter }
+	}
+}
@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+use crate::{RawThreadCounter, ThreadCounter};
+use alloc::sync::{Arc, Weak};
+
+/// A non-owning handle to a [`ThreadCounter`], created by
+/// [`ThreadCounter::downgrade()`].
+///
+/// This mirrors [`Weak`]: it doesn't keep the underlying counter alive, and
+/// has to be upgraded back into a [`ThreadCounter`] before it can be used.
+/// This lets observers hold onto a counter without keeping its allocation
+/// alive unnecessarily.
+///
+/// ```rust
+/// use thread_counter::ThreadCounter;
+///
+/// let counter = ThreadCounter::default();
+/// let weak = counter.downgrade();
+/// assert!(weak.upgrade().is_some());
+///
+/// drop(counter);
+/// assert!(weak.upgrade().is_none());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WeakCounter {
+	inner: Weak<RawThreadCounter>,
+}
+
+impl WeakCounter {
+	/// Attempts to upgrade this into a [`ThreadCounter`].
+	///
+	/// # Returns
+	/// * `Some` with the counter, if it's still alive.
+	/// * `None` if every [`ThreadCounter`] (and [`Ticket`](crate::Ticket))
+	///   referring to it has been dropped.
+	pub fn upgrade(&self) -> Option<ThreadCounter> {
+		self.inner.upgrade().map(|inner| ThreadCounter { inner })
+	}
+}
+
+impl ThreadCounter {
+	/// Creates a new [`WeakCounter`] referring to this counter, which doesn't
+	/// keep it alive.
+	pub fn downgrade(&self) -> WeakCounter {
+		WeakCounter {
+			inner: Arc::downgrade(&self.inner),
+		}
+	}
+}
@@ -0,0 +1,122 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Model-checks the counter's notify/wait logic with loom.
+//!
+//! Run with:
+//!
+//! ```sh
+//! RUSTFLAGS="--cfg loom" cargo test --release --test loom
+//! ```
+#![cfg(loom)]
+
+use loom::{
+	sync::atomic::{AtomicBool, Ordering},
+	thread,
+};
+use std::sync::Arc;
+use thread_counter::ThreadCounter;
+
+#[test]
+fn wait_sees_ticket_drop() {
+	loom::model(|| {
+		let counter = ThreadCounter::default();
+		let ticket = counter.ticket().unwrap();
+		let worker = thread::spawn(move || drop(ticket));
+
+		assert!(counter.wait_indefinitely().is_completed());
+		assert_eq!(counter.count(), 0);
+		worker.join().unwrap();
+	});
+}
+
+#[test]
+fn wait_sees_concurrent_decrements() {
+	loom::model(|| {
+		let counter = ThreadCounter::with_count(2);
+		let workers: Vec<_> = (0..2)
+			.map(|_| {
+				let counter = counter.clone();
+				thread::spawn(move || counter.decrement())
+			})
+			.collect();
+
+		assert!(counter.wait_indefinitely().is_completed());
+		assert_eq!(counter.count(), 0);
+		for worker in workers {
+			worker.join().unwrap();
+		}
+	});
+}
+
+#[test]
+fn wait_for_sees_partial_drain() {
+	loom::model(|| {
+		let counter = ThreadCounter::with_count(2);
+		let worker = thread::spawn({
+			let counter = counter.clone();
+			move || counter.decrement()
+		});
+
+		assert!(counter.wait_for(1, None).is_completed());
+		assert!(counter.count() <= 1);
+		worker.join().unwrap();
+	});
+}
+
+#[test]
+fn no_tickets_outlive_close() {
+	loom::model(|| {
+		let counter = ThreadCounter::default();
+		let holding = Arc::new(AtomicBool::new(false));
+		let worker = thread::spawn({
+			let counter = counter.clone();
+			let holding = holding.clone();
+			move || {
+				if let Ok(ticket) = counter.ticket() {
+					holding.store(true, Ordering::SeqCst);
+					holding.store(false, Ordering::SeqCst);
+					drop(ticket);
+				}
+			}
+		});
+
+		counter.close();
+		assert!(counter.wait_indefinitely().is_completed());
+		assert!(!holding.load(Ordering::SeqCst));
+		worker.join().unwrap();
+	});
+}
+
+#[test]
+fn notify_all_interrupts_waiter() {
+	loom::model(|| {
+		let counter = ThreadCounter::with_count(1);
+		let waiter = thread::spawn({
+			let counter = counter.clone();
+			move || counter.wait_indefinitely()
+		});
+
+		while counter.waiters() == 0 {
+			thread::yield_now();
+		}
+		counter.notify_all();
+		assert!(waiter.join().unwrap().is_interrupted());
+	});
+}
+
+#[test]
+fn notify_all_interrupts_wait_for() {
+	loom::model(|| {
+		let counter = ThreadCounter::with_count(3);
+		let waiter = thread::spawn({
+			let counter = counter.clone();
+			move || counter.wait_for(1, None)
+		});
+
+		while counter.waiters() == 0 {
+			thread::yield_now();
+		}
+		counter.notify_all();
+		assert!(waiter.join().unwrap().is_interrupted());
+		assert_eq!(counter.count(), 3);
+	});
+}